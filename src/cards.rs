@@ -0,0 +1,269 @@
+use crate::parser::{CardType, Hand, SuitedType, CARD_TYPES};
+use crate::range::ComboRange;
+use std::fmt::{self, Display};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum Suit {
+    Spades,
+    Hearts,
+    Diamonds,
+    Clubs,
+}
+
+pub const SUITS: [Suit; 4] = [Suit::Spades, Suit::Hearts, Suit::Diamonds, Suit::Clubs];
+
+/// The number of distinct two-card starting hands.
+pub const TOTAL_COMBOS: usize = 1326;
+
+/// Returns the combos of `hands`, each listed once.
+pub fn expand_hands(hands: &[Hand]) -> Vec<Combo> {
+    let mut seen = ComboRange::new();
+    let mut combos: Vec<Combo> = Vec::new();
+    for hand in hands {
+        for combo in hand.combos() {
+            if seen.insert(&combo) {
+                combos.push(combo);
+            }
+        }
+    }
+    combos
+}
+
+/// Returns `combo_count` as a percentage of all starting hands, e.g. 188 combos is 14.18%.
+pub fn combo_percentage(combo_count: usize) -> f64 {
+    combo_count as f64 / TOTAL_COMBOS as f64 * 100.0
+}
+
+impl Suit {
+    pub fn from_char(character: char) -> Option<Self> {
+        match character {
+            's' => Some(Suit::Spades),
+            'h' => Some(Suit::Hearts),
+            'd' => Some(Suit::Diamonds),
+            'c' => Some(Suit::Clubs),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let suit_display = match self {
+            Suit::Spades => 's',
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+        };
+        write!(f, "{}", suit_display)
+    }
+}
+
+/// A single playing card, e.g. `Ah`.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct Card {
+    rank: CardType,
+    suit: Suit,
+}
+
+impl Card {
+    pub fn new(rank: CardType, suit: Suit) -> Self {
+        Card { rank, suit }
+    }
+
+    pub fn rank(&self) -> CardType {
+        self.rank
+    }
+
+    pub fn suit(&self) -> Suit {
+        self.suit
+    }
+
+    /// Returns a unique index from 0 to 51, ordered by rank from the aces down and then by suit.
+    pub fn index(&self) -> usize {
+        self.rank.index() * 4 + self.suit as usize
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Card::new(CARD_TYPES[index / 4], SUITS[index % 4])
+    }
+}
+
+impl Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+/// One of the 1326 concrete two-card starting hands, e.g. `AhKh`. The higher ranked card is
+/// always stored first so that `KhAh` and `AhKh` are the same combo.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub struct Combo(Card, Card);
+
+impl Combo {
+    pub fn new(first_card: Card, second_card: Card) -> Self {
+        assert!(
+            first_card != second_card,
+            "A combo can't contain {} twice",
+            first_card
+        );
+        if (second_card.rank, first_card.suit) > (first_card.rank, second_card.suit) {
+            Combo(second_card, first_card)
+        } else {
+            Combo(first_card, second_card)
+        }
+    }
+
+    pub fn first_card(&self) -> Card {
+        self.0
+    }
+
+    pub fn second_card(&self) -> Card {
+        self.1
+    }
+
+    pub fn has_card(&self, card: &Card) -> bool {
+        self.0 == *card || self.1 == *card
+    }
+
+    /// Returns a unique index from 0 to 1325. The first card always has the lower card index,
+    /// so combos are numbered like the entries of a lower triangular matrix.
+    pub fn index(&self) -> usize {
+        let (low, high) = (self.0.index(), self.1.index());
+        high * (high - 1) / 2 + low
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        let mut high = 1;
+        while (high + 1) * high / 2 <= index {
+            high += 1;
+        }
+        let low = index - high * (high - 1) / 2;
+        Combo(Card::from_index(low), Card::from_index(high))
+    }
+
+    /// Returns the hand class this combo belongs to, e.g. `AKs` for `AhKh`.
+    pub fn hand(&self) -> Hand {
+        let suited_type = if self.0.rank == self.1.rank {
+            SuitedType::None
+        } else if self.0.suit == self.1.suit {
+            SuitedType::Suited
+        } else {
+            SuitedType::Offsuit
+        };
+        Hand::new(self.0.rank, self.1.rank, suited_type)
+    }
+}
+
+impl Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.0, self.1)
+    }
+}
+
+/// The combos of a range that are still possible once some cards are known to be dead, e.g.
+/// our hole cards or the board, grouped by hand class.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LiveRange {
+    hands: Vec<(Hand, Vec<Combo>)>,
+}
+
+impl LiveRange {
+    pub fn new(hands: &[Hand], dead_cards: &[Card]) -> Self {
+        let mut live_hands: Vec<(Hand, Vec<Combo>)> = Vec::new();
+        for hand in hands {
+            if live_hands.iter().any(|(existing, _)| existing == hand) {
+                continue;
+            }
+            let combos: Vec<Combo> = hand
+                .combos()
+                .into_iter()
+                .filter(|combo| !dead_cards.iter().any(|card| combo.has_card(card)))
+                .collect();
+            if !combos.is_empty() {
+                live_hands.push((hand.clone(), combos));
+            }
+        }
+        LiveRange { hands: live_hands }
+    }
+
+    /// Returns how many combos of `hand` are still possible.
+    pub fn hand_combo_count(&self, hand: &Hand) -> usize {
+        self.hands
+            .iter()
+            .find(|(existing, _)| existing == hand)
+            .map_or(0, |(_, combos)| combos.len())
+    }
+
+    pub fn combo_count(&self) -> usize {
+        self.hands.iter().map(|(_, combos)| combos.len()).sum()
+    }
+
+    pub fn combos(&self) -> impl Iterator<Item = &Combo> {
+        self.hands.iter().flat_map(|(_, combos)| combos.iter())
+    }
+
+    /// Iterates over each hand class that still has live combos, along with those combos.
+    pub fn iter(&self) -> impl Iterator<Item = (&Hand, &[Combo])> {
+        self.hands
+            .iter()
+            .map(|(hand, combos)| (hand, combos.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn orders_combo_cards() {
+        let combo = Combo::new(
+            Card::new(CardType::King, Suit::Hearts),
+            Card::new(CardType::Ace, Suit::Hearts),
+        );
+        assert_eq!(combo.to_string(), "AhKh");
+        assert_eq!(
+            combo.hand(),
+            Hand::new(CardType::Ace, CardType::King, SuitedType::Suited)
+        );
+
+        let pair = Combo::new(
+            Card::new(CardType::Queen, Suit::Diamonds),
+            Card::new(CardType::Queen, Suit::Spades),
+        );
+        assert_eq!(pair.to_string(), "QsQd");
+        assert!(pair.hand().is_pair());
+    }
+
+    #[test]
+    fn indexes_combos() {
+        for index in 0..TOTAL_COMBOS {
+            assert_eq!(Combo::from_index(index).index(), index);
+        }
+        let combo = Combo::new(
+            Card::new(CardType::Two, Suit::Clubs),
+            Card::new(CardType::Two, Suit::Diamonds),
+        );
+        assert_eq!(combo.index(), TOTAL_COMBOS - 1);
+    }
+
+    #[test]
+    fn removes_dead_cards() {
+        let hands = Parser::new("AA, AKs, AKo, KQs").parse_range_list().unwrap();
+        let dead_cards = Parser::new("Ah 7c 2d").parse_cards().unwrap();
+        let live_range = LiveRange::new(&hands, &dead_cards);
+
+        let aces = Hand::new(CardType::Ace, CardType::Ace, SuitedType::None);
+        let ace_king_suited = Hand::new(CardType::Ace, CardType::King, SuitedType::Suited);
+        let ace_king_offsuit = Hand::new(CardType::Ace, CardType::King, SuitedType::Offsuit);
+        let king_queen_suited = Hand::new(CardType::King, CardType::Queen, SuitedType::Suited);
+        assert_eq!(live_range.hand_combo_count(&aces), 3);
+        assert_eq!(live_range.hand_combo_count(&ace_king_suited), 3);
+        assert_eq!(live_range.hand_combo_count(&ace_king_offsuit), 9);
+        assert_eq!(live_range.hand_combo_count(&king_queen_suited), 4);
+        assert_eq!(live_range.combo_count(), 19);
+        assert!(live_range
+            .combos()
+            .all(|combo| !dead_cards.iter().any(|card| combo.has_card(card))));
+    }
+}
@@ -0,0 +1,311 @@
+use crate::grid::{grid_hand, GRID_SIZE};
+use crate::parser::{Hand, ParseResult, Parser, WeightedRange};
+use crate::range::Range;
+use crate::PositionInput;
+use std::fmt::{self, Display, Write};
+
+const CELL_SIZE: usize = 40;
+const TITLE_HEIGHT: usize = 32;
+const LEGEND_HEIGHT: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Raise,
+    Call,
+    Fold,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Raise => write!(f, "Raise"),
+            Action::Call => write!(f, "Call"),
+            Action::Fold => write!(f, "Fold"),
+        }
+    }
+}
+
+/// The fill colours used for each action, as any CSS colour value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartColors {
+    pub raise: String,
+    pub call: String,
+    pub fold: String,
+}
+
+impl Default for ChartColors {
+    fn default() -> Self {
+        ChartColors {
+            raise: "#e4572e".to_string(),
+            call: "#4caf50".to_string(),
+            fold: "#dcdcdc".to_string(),
+        }
+    }
+}
+
+impl ChartColors {
+    pub fn color(&self, action: Action) -> &str {
+        match action {
+            Action::Raise => &self.raise,
+            Action::Call => &self.call,
+            Action::Fold => &self.fold,
+        }
+    }
+}
+
+/// A 13x13 chart of what to do with each hand. Every hand folds whatever share of the time
+/// isn't taken up by the raise and call ranges.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    title: String,
+    raise: WeightedRange,
+    call: WeightedRange,
+    colors: ChartColors,
+}
+
+impl Chart {
+    pub fn new(title: &str) -> Self {
+        Chart {
+            title: title.to_string(),
+            raise: WeightedRange::new(),
+            call: WeightedRange::new(),
+            colors: ChartColors::default(),
+        }
+    }
+
+    /// Builds a chart that raises every hand in `range`.
+    pub fn from_range(title: &str, range: &Range) -> Self {
+        let mut raise = WeightedRange::new();
+        for hand in range.iter() {
+            raise.insert(hand.clone(), 1.0);
+        }
+        Chart::new(title).with_raise(raise)
+    }
+
+    /// Builds a chart from a position in a range file, raising the `raise` hands every time
+    /// and the `raise_or_fold` hands half the time. A hand in both lists is always raised.
+    pub fn from_position_input(position_input: &PositionInput) -> ParseResult<Self> {
+        let mut raise = Parser::new(&position_input.schema.raise).parse_weighted_range_list()?;
+        let raise_or_fold =
+            Parser::new(&position_input.schema.raise_or_fold).parse_weighted_range_list()?;
+        for (hand, frequency) in raise_or_fold.iter() {
+            if !raise.contains(hand) {
+                raise.insert(hand.clone(), frequency * 0.5);
+            }
+        }
+        Ok(Chart::new(&position_input.position).with_raise(raise))
+    }
+
+    pub fn with_raise(mut self, range: WeightedRange) -> Self {
+        self.raise = range;
+        self
+    }
+
+    pub fn with_call(mut self, range: WeightedRange) -> Self {
+        self.call = range;
+        self
+    }
+
+    pub fn with_colors(mut self, colors: ChartColors) -> Self {
+        self.colors = colors;
+        self
+    }
+
+    /// Returns how often `hand` takes each action. The frequencies always add up to 1.0.
+    pub fn frequencies(&self, hand: &Hand) -> [(Action, f64); 3] {
+        let raise = self.raise.frequency(hand).clamp(0.0, 1.0);
+        let call = self.call.frequency(hand).clamp(0.0, 1.0 - raise);
+        [
+            (Action::Raise, raise),
+            (Action::Call, call),
+            (Action::Fold, 1.0 - raise - call),
+        ]
+    }
+
+    /// Renders the chart as a standalone SVG image with a title and a legend.
+    pub fn to_svg(&self) -> String {
+        let width = CELL_SIZE * GRID_SIZE;
+        let height = TITLE_HEIGHT + CELL_SIZE * GRID_SIZE + LEGEND_HEIGHT;
+        let mut svg = String::new();
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}" font-family="sans-serif">"#,
+            width, height
+        );
+        let _ = writeln!(
+            svg,
+            r#"  <text x="{}" y="{}" font-size="18" text-anchor="middle">{}</text>"#,
+            width / 2,
+            TITLE_HEIGHT - 10,
+            escape(&self.title)
+        );
+        for row in 0..GRID_SIZE {
+            for column in 0..GRID_SIZE {
+                self.write_cell(&mut svg, row, column);
+            }
+        }
+        self.write_legend(&mut svg, TITLE_HEIGHT + CELL_SIZE * GRID_SIZE);
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the chart as a self-contained HTML page that embeds the SVG image.
+    pub fn to_html(&self) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>body {{ font-family: sans-serif; margin: 2em; }}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+            escape(&self.title),
+            self.to_svg()
+        )
+    }
+
+    fn write_cell(&self, svg: &mut String, row: usize, column: usize) {
+        let hand = grid_hand(row, column);
+        let x = column * CELL_SIZE;
+        let y = TITLE_HEIGHT + row * CELL_SIZE;
+        // Each action fills a share of the cell's width matching its frequency.
+        let mut offset = 0.0;
+        for (action, frequency) in self.frequencies(&hand) {
+            if frequency <= 0.0 {
+                continue;
+            }
+            let bar_width = frequency * CELL_SIZE as f64;
+            let _ = writeln!(
+                svg,
+                r#"  <rect x="{:.2}" y="{}" width="{:.2}" height="{}" fill="{}"/>"#,
+                x as f64 + offset,
+                y,
+                bar_width,
+                CELL_SIZE,
+                escape(self.colors.color(action))
+            );
+            offset += bar_width;
+        }
+        let _ = writeln!(
+            svg,
+            r##"  <rect x="{}" y="{}" width="{2}" height="{2}" fill="none" stroke="#ffffff"/>"##,
+            x, y, CELL_SIZE
+        );
+        let _ = writeln!(
+            svg,
+            r#"  <text x="{}" y="{}" font-size="12" text-anchor="middle">{}</text>"#,
+            x + CELL_SIZE / 2,
+            y + CELL_SIZE / 2 + 4,
+            hand
+        );
+    }
+
+    fn write_legend(&self, svg: &mut String, y: usize) {
+        for (i, action) in [Action::Raise, Action::Call, Action::Fold]
+            .iter()
+            .enumerate()
+        {
+            let x = 10 + i * 100;
+            let _ = writeln!(
+                svg,
+                r#"  <rect x="{}" y="{}" width="14" height="14" fill="{}"/>"#,
+                x,
+                y + 9,
+                escape(self.colors.color(*action))
+            );
+            let _ = writeln!(
+                svg,
+                r#"  <text x="{}" y="{}" font-size="14">{}</text>"#,
+                x + 20,
+                y + 21,
+                action
+            );
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_range_file, Schema};
+
+    #[test]
+    fn splits_frequencies_between_actions() {
+        let raise = Parser::new("AA, AKs:0.5")
+            .parse_weighted_range_list()
+            .unwrap();
+        let call = Parser::new("AKs, QQ").parse_weighted_range_list().unwrap();
+        let chart = Chart::new("BTN vs CO").with_raise(raise).with_call(call);
+        let frequencies = |range: &str| {
+            let hand = Parser::new(range).parse_range().unwrap().remove(0);
+            chart.frequencies(&hand).map(|(_, frequency)| frequency)
+        };
+        assert_eq!(frequencies("AA"), [1.0, 0.0, 0.0]);
+        assert_eq!(frequencies("AKs"), [0.5, 0.5, 0.0]);
+        assert_eq!(frequencies("QQ"), [0.0, 1.0, 0.0]);
+        assert_eq!(frequencies("72o"), [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn raises_hands_listed_as_both_raise_and_raise_or_fold() {
+        let position_input = PositionInput {
+            position: "CO".to_string(),
+            schema: Schema {
+                raise: "77+, AJs".to_string(),
+                raise_or_fold: "55-88, AJs, ATs".to_string(),
+            },
+        };
+        let chart = Chart::from_position_input(&position_input).unwrap();
+        let raise_frequency = |range: &str| {
+            let hand = Parser::new(range).parse_range().unwrap().remove(0);
+            chart.frequencies(&hand)[0].1
+        };
+        assert_eq!(raise_frequency("77"), 1.0);
+        assert_eq!(raise_frequency("AJs"), 1.0);
+        assert_eq!(raise_frequency("66"), 0.5);
+        assert_eq!(raise_frequency("ATs"), 0.5);
+    }
+
+    #[test]
+    fn renders_svg_and_html() {
+        let position_input = PositionInput {
+            position: "UTG <6-max>".to_string(),
+            schema: Schema {
+                raise: "77+, AQo+".to_string(),
+                raise_or_fold: "55-66".to_string(),
+            },
+        };
+        let chart = Chart::from_position_input(&position_input)
+            .unwrap()
+            .with_colors(ChartColors {
+                raise: "crimson".to_string(),
+                ..ChartColors::default()
+            });
+        let svg = chart.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("UTG &lt;6-max&gt;"));
+        assert!(svg.contains(r#"width="40.00" height="40" fill="crimson""#));
+        assert!(svg.contains(r#"width="20.00" height="40" fill="crimson""#));
+        assert_eq!(svg.matches("<text").count(), 1 + 169 + 3);
+
+        let html = chart.to_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>UTG &lt;6-max&gt;</title>"));
+        assert!(html.contains(&svg));
+    }
+
+    #[test]
+    fn loads_range_files() {
+        let position_inputs = load_range_file("src/6-max rfi.toml").unwrap();
+        assert_eq!(position_inputs.len(), 5);
+        let utg = position_inputs
+            .iter()
+            .find(|position_input| position_input.position == "utg")
+            .unwrap();
+        assert_eq!(utg.schema.raise, "AQo+, A5s, A9s+, KTs+, QTs+, JTs+, 77+");
+        for position_input in position_inputs.iter() {
+            assert!(Chart::from_position_input(position_input).is_ok());
+        }
+    }
+}
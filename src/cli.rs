@@ -1,28 +1,396 @@
-pub mod cli {
-    use clap::{ArgEnum, Parser};
-    use std::fmt;
+use crate::formats::{self, Dialect};
+use crate::lexer::Spelling;
+use crate::parser::{ErrorKind, ParseError, ParseResult, Parser, WeightedRange};
+use crate::range::WeightedComboRange;
+use clap::{ArgEnum, Parser as ClapParser, Subcommand};
+use serde::Serialize;
+use std::error::Error;
+use std::fmt;
 
-    #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum)]
-    pub enum Mode {
-        Beginner,
-        Advanced,
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ArgEnum)]
+pub enum Mode {
+    Beginner,
+    Advanced,
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Beginner => write!(f, "beginner"),
+            Mode::Advanced => write!(f, "advanced"),
+        }
     }
+}
 
-    impl fmt::Display for Mode {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            match self {
-                Mode::Beginner => write!(f, "beginner"),
-                Mode::Advanced => write!(f, "advanced"),
+impl Mode {
+    /// Parses a range string the way this mode allows: beginner ranges are raise/fold only,
+    /// so frequencies are rejected, while advanced ranges may carry them.
+    pub fn parse_range(&self, range: &str, spelling: Spelling) -> ParseResult<WeightedRange> {
+        match self {
+            Mode::Beginner => {
+                let mut weighted_range = WeightedRange::new();
+                for hand in Parser::new(range)
+                    .with_spelling(spelling)
+                    .parse_range_list()
+                    .map_err(|error| beginner_error(error, range, spelling))?
+                {
+                    weighted_range.insert(hand, 1.0);
+                }
+                Ok(weighted_range)
             }
+            Mode::Advanced => Parser::new(range)
+                .with_spelling(spelling)
+                .parse_weighted_range_list(),
+        }
+    }
+
+    /// Parses a range string that may also list specific combos, such as `QQ+, AhKh`, into
+    /// a frequency for each combo. Frequencies are only allowed in advanced mode.
+    pub fn parse_combo_range(
+        &self,
+        range: &str,
+        spelling: Spelling,
+    ) -> ParseResult<WeightedComboRange> {
+        let mut parser = Parser::new(range).with_spelling(spelling);
+        let elements = match self {
+            Mode::Beginner => parser
+                .parse_element_list()
+                .map_err(|error| beginner_error(error, range, spelling))?
+                .into_iter()
+                .map(|element| (element, 1.0))
+                .collect(),
+            Mode::Advanced => parser.parse_weighted_element_list()?,
+        };
+        let mut combo_range = WeightedComboRange::new();
+        for (element, frequency) in elements {
+            for combo in element.combos() {
+                combo_range.set(&combo, frequency);
+            }
+        }
+        Ok(combo_range)
+    }
+
+    /// Parses a range string like `parse_range`, but carries on past errors, returning the
+    /// hands that could be parsed along with every error found.
+    pub fn recover_range(
+        &self,
+        range: &str,
+        spelling: Spelling,
+    ) -> (WeightedRange, Vec<ParseError>) {
+        match self {
+            Mode::Beginner => {
+                let (hands, errors) = Parser::new(range)
+                    .with_spelling(spelling)
+                    .recover_range_list();
+                let mut weighted_range = WeightedRange::new();
+                for hand in hands {
+                    weighted_range.insert(hand, 1.0);
+                }
+                (weighted_range, errors)
+            }
+            Mode::Advanced => Parser::new(range)
+                .with_spelling(spelling)
+                .recover_weighted_range_list(),
+        }
+    }
+}
+
+/// Points out when a range only failed to parse in beginner mode because it has frequencies,
+/// which advanced mode would accept.
+fn beginner_error(error: ParseError, range: &str, spelling: Spelling) -> ParseError {
+    let weighted = Parser::new(range)
+        .with_spelling(spelling)
+        .parse_weighted_element_list();
+    if weighted.is_err() {
+        return error;
+    }
+    ParseError::new(
+        ErrorKind::InvalidFrequency("frequencies are only allowed in advanced mode".to_string()),
+        error.span(),
+        error.source_text(),
+    )
+}
+
+/// The range string formats that `convert` reads and writes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, ArgEnum)]
+pub enum Format {
+    Notation,
+    Solver,
+    /// Also reads and writes Equilab ranges, which use the same layout
+    #[clap(alias = "equilab")]
+    Pokerstove,
+    Flopzilla,
+}
+
+impl Format {
+    /// Reads a range written in this format. Notation follows the mode, so beginner mode
+    /// rejects frequencies, but solver ranges are weighted by definition and keep their
+    /// weights in either mode. The equity tool dialects can't carry weights at all.
+    pub fn read(
+        &self,
+        text: &str,
+        mode: Mode,
+        spelling: Spelling,
+    ) -> Result<WeightedComboRange, Box<dyn Error>> {
+        let dialect = match self {
+            Format::Notation => return Ok(mode.parse_combo_range(text, spelling)?),
+            Format::Solver => return Ok(formats::read_notation(text, spelling)?),
+            Format::Pokerstove => Dialect::PokerStove,
+            Format::Flopzilla => Dialect::Flopzilla,
+        };
+        let range = formats::import_range(text, dialect, spelling)?;
+        Ok(WeightedComboRange::from(&range))
+    }
+}
+
+/// The JSON printed by `parse`: every hand class in the range with its frequency and combos,
+/// followed by the totals.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParseReport {
+    pub hands: Vec<HandReport>,
+    pub hand_count: usize,
+    pub combo_count: f64,
+    pub percentage: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HandReport {
+    pub hand: String,
+    pub frequency: f64,
+    pub combos: Vec<String>,
+}
+
+impl ParseReport {
+    pub fn new(range: &WeightedRange) -> Self {
+        ParseReport {
+            hands: range
+                .iter()
+                .map(|(hand, frequency)| HandReport {
+                    hand: hand.to_string(),
+                    frequency,
+                    combos: hand
+                        .combos()
+                        .iter()
+                        .map(|combo| combo.to_string())
+                        .collect(),
+                })
+                .collect(),
+            hand_count: range.len(),
+            combo_count: range.combo_count(),
+            percentage: range.percentage(),
         }
     }
+}
+
+#[derive(ClapParser, Debug)]
+#[clap(author, version,long_about = None)]
+pub struct Args {
+    /// Beginner mode is raise/fold, advanced mode involves ratios
+    #[clap(long, arg_enum, default_value_t = Mode::Beginner, global = true)]
+    pub mode: Mode,
+
+    /// Only accept canonical range notation, rather than also reading words like `ace king
+    /// suited`, lowercase hands, `10` for a ten and suit symbols
+    #[clap(long, global = true)]
+    pub strict: bool,
+
+    /// Runs the interactive prompt when no command is given
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Args {
+    pub fn spelling(&self) -> Spelling {
+        if self.strict {
+            Spelling::Strict
+        } else {
+            Spelling::Forgiving
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Print the hands in a range as JSON
+    Parse {
+        /// Read the range from a file instead of the arguments; `-` reads from stdin
+        #[clap(long, short)]
+        file: Option<String>,
+        /// The range to parse; read from stdin when neither this nor --file is given
+        ranges: Vec<String>,
+    },
+    /// Print a range as a 13x13 grid
+    Grid { ranges: Vec<String> },
+    /// Count the combos in a range
+    Count { ranges: Vec<String> },
+    /// Convert a range between formats
+    Convert {
+        #[clap(long, arg_enum, default_value_t = Format::Notation)]
+        from: Format,
+        #[clap(long, arg_enum, default_value_t = Format::Notation)]
+        to: Format,
+        ranges: Vec<String>,
+    },
+    /// Work out how often each range wins all-in
+    Equity {
+        /// The cards already on the board, e.g. `Ah7c2d`
+        #[clap(long, short)]
+        board: Option<String>,
+        /// Cards out of play that aren't on the board
+        #[clap(long, short)]
+        dead: Option<String>,
+        /// Deal every possible board rather than sampling random ones
+        #[clap(long)]
+        exact: bool,
+        /// How many random deals to sample
+        #[clap(long, default_value_t = 100_000)]
+        samples: usize,
+        /// Seeds the sampling so a result can be repeated; random when not given
+        #[clap(long)]
+        seed: Option<u64>,
+        /// One range per player, e.g. `QQ+, AKs` `AhKh`
+        ranges: Vec<String>,
+    },
+    /// Quiz yourself on the hands in a range file
+    Train { file: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        let args = Args::parse_from(["pfrange", "grid", "22+", "A2s+", "--mode", "advanced"]);
+        assert_eq!(args.mode, Mode::Advanced);
+        assert!(
+            matches!(args.command, Some(Command::Grid { ranges }) if ranges == ["22+", "A2s+"])
+        );
+
+        let args = Args::parse_from(["pfrange", "convert", "--to", "solver", "AKs"]);
+        assert_eq!(args.mode, Mode::Beginner);
+        assert!(matches!(
+            args.command,
+            Some(Command::Convert {
+                from: Format::Notation,
+                to: Format::Solver,
+                ..
+            })
+        ));
+        let args = Args::parse_from(["pfrange", "convert", "--from", "equilab", "AKs"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Convert {
+                from: Format::Pokerstove,
+                ..
+            })
+        ));
+        assert!(Args::parse_from(["pfrange"]).command.is_none());
+        assert_eq!(
+            Args::parse_from(["pfrange"]).spelling(),
+            Spelling::Forgiving
+        );
+        assert_eq!(
+            Args::parse_from(["pfrange", "count", "--strict", "AA"]).spelling(),
+            Spelling::Strict
+        );
+    }
+
+    #[test]
+    fn mode_controls_frequencies() {
+        let error = Mode::Beginner
+            .parse_range("AA, AKs:0.5", Spelling::Strict)
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidFrequency(_)));
+        assert_eq!(error.span(), 7..8);
+        let error = Mode::Beginner
+            .parse_combo_range("AKs:0.5", Spelling::Strict)
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidFrequency(_)));
+        let error = Mode::Beginner
+            .parse_range("AA, AKz", Spelling::Strict)
+            .unwrap_err();
+        assert!(matches!(error.kind(), ErrorKind::InvalidToken(_)));
+        let range = Mode::Advanced
+            .parse_range("AA, AKs:0.5", Spelling::Strict)
+            .unwrap();
+        assert_eq!(range.combo_count(), 8.0);
+
+        let (range, errors) = Mode::Beginner.recover_range("AA, AKs:0.5, KK", Spelling::Strict);
+        assert_eq!(range.combo_count(), 16.0);
+        assert_eq!(errors.len(), 1);
+    }
 
-    #[derive(Parser, Debug)]
-    #[clap(author, version,long_about = None)]
-    pub struct Args {
-        /// Beginner mode is raise/fold, advanced mode involves ratios
-        #[clap(arg_enum, default_value_t = Mode::Beginner)]
-        pub mode: Mode,
+    #[test]
+    fn converts_solver_ranges_in_either_mode() {
+        let convert = |arguments: &[&str]| {
+            let args = Args::parse_from(arguments);
+            let spelling = args.spelling();
+            let Some(Command::Convert { from, ranges, .. }) = args.command else {
+                panic!("not a convert command");
+            };
+            from.read(&ranges.join(" "), args.mode, spelling)
+                .map(|range| formats::write_notation(&range))
+                .map_err(|error| error.to_string())
+        };
+        let solver = ["pfrange", "convert", "--from", "solver", "--to", "notation"];
+        assert_eq!(
+            convert(&[&solver[..], &["AA:1,AKs:1"]].concat()),
+            Ok("AA, AKs".to_string())
+        );
+        assert_eq!(
+            convert(&[&solver[..], &["AA:1,AKs:0.5"]].concat()),
+            Ok("AA, AKs:0.5".to_string())
+        );
+        assert_eq!(
+            convert(&[&solver[..], &["--mode", "advanced", "AA:1,AKs:0.5"]].concat()),
+            Ok("AA, AKs:0.5".to_string())
+        );
+        assert!(convert(&["pfrange", "convert", "AA:1,AKs:0.5"]).is_err());
     }
 
+    #[test]
+    fn reports_parsed_range() {
+        let range = Mode::Advanced
+            .parse_range("AA, AKs:0.5", Spelling::Strict)
+            .unwrap();
+        let report = ParseReport::new(&range);
+        assert_eq!(report.hand_count, 2);
+        assert_eq!(report.combo_count, 8.0);
+        assert_eq!(report.hands[1].hand, "AKs");
+        assert_eq!(report.hands[1].frequency, 0.5);
+        assert_eq!(report.hands[1].combos.len(), 4);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["hands"][0]["combos"].as_array().unwrap().len(), 6);
+        assert_eq!(json["combo_count"], 8.0);
+
+        let args = Args::parse_from(["pfrange", "parse", "-f", "-"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Parse { file: Some(file), ranges }) if file == "-" && ranges.is_empty()
+        ));
+    }
+
+    #[test]
+    fn parses_equity_command() {
+        let args = Args::parse_from(["pfrange", "equity", "QQ+, AKs", "AhKh", "-b", "Ah7c2d"]);
+        assert!(matches!(
+            args.command,
+            Some(Command::Equity { board: Some(board), exact: false, samples: 100_000, seed: None, ranges, .. })
+                if board == "Ah7c2d" && ranges == ["QQ+, AKs", "AhKh"]
+        ));
+
+        let range = Mode::Beginner
+            .parse_combo_range("QQ+, AhKh", Spelling::Strict)
+            .unwrap();
+        assert_eq!(range.combo_count(), 19.0);
+        assert!(Mode::Beginner
+            .parse_combo_range("AhKh:0.5", Spelling::Strict)
+            .is_err());
+        let range = Mode::Advanced
+            .parse_combo_range("AhKh:0.5", Spelling::Strict)
+            .unwrap();
+        assert_eq!(range.combo_count(), 0.5);
+    }
 }
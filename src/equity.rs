@@ -0,0 +1,548 @@
+use crate::cards::{Card, Combo};
+use crate::evaluator::{self, HandRank};
+use crate::range::WeightedComboRange;
+use crate::utils::Rng;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// The number of times in a row Monte Carlo sampling may fail to deal every player a hand
+/// before checking whether the ranges can be dealt together at all.
+const MAX_FAILED_DEALS: usize = 100_000;
+
+/// How the hands and the rest of the board are dealt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// Deals every possible combination of hands and boards. The answer is exact, but wide
+    /// ranges without a flop can take a long time.
+    Exact,
+    /// Deals `samples` random hands and boards from a generator seeded with `seed`, so the
+    /// same seed always gives the same answer.
+    MonteCarlo { samples: usize, seed: u64 },
+}
+
+impl Default for Method {
+    fn default() -> Self {
+        Method::MonteCarlo {
+            samples: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+/// How one player's range does at showdown, as shares of every deal.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Equity {
+    /// How often the player wins the whole pot.
+    pub win: f64,
+    /// How often the player splits the pot with at least one other player.
+    pub tie: f64,
+    /// The share of the pot the player wins on average, where a pot split `n` ways is worth
+    /// `1/n` to each player in it.
+    pub equity: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EquityError {
+    TooFewRanges(usize),
+    TooManyBoardCards(usize),
+    DuplicateCard(Card),
+    /// Every combo in the range of the player at this index is blocked by the board or the
+    /// dead cards.
+    EmptyRange(usize),
+    /// The ranges can't be dealt together, e.g. `AhAd` against `AhKh`.
+    NoDeal,
+    /// Monte Carlo sampling was asked for zero samples.
+    NoSamples,
+}
+
+impl Display for EquityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EquityError::TooFewRanges(count) => {
+                write!(f, "Equity needs at least two ranges, got {}", count)
+            }
+            EquityError::TooManyBoardCards(count) => {
+                write!(f, "A board has at most 5 cards, got {}", count)
+            }
+            EquityError::DuplicateCard(card) => write!(f, "{} is dealt more than once", card),
+            EquityError::EmptyRange(player) => write!(
+                f,
+                "Range {} has no combos left once the board and dead cards are removed",
+                player + 1
+            ),
+            EquityError::NoDeal => write!(f, "The ranges can't be dealt together"),
+            EquityError::NoSamples => write!(f, "Monte Carlo sampling needs at least one sample"),
+        }
+    }
+}
+
+impl Error for EquityError {}
+
+/// Works out how often each of two or more ranges wins at showdown, given an optional partial
+/// board and dead cards. Combos are weighted by their frequency in the range.
+#[derive(Debug, Clone)]
+pub struct EquityCalculator {
+    ranges: Vec<WeightedComboRange>,
+    board: Vec<Card>,
+    dead_cards: Vec<Card>,
+    method: Method,
+}
+
+impl EquityCalculator {
+    pub fn new(ranges: Vec<WeightedComboRange>) -> Self {
+        EquityCalculator {
+            ranges,
+            board: Vec::new(),
+            dead_cards: Vec::new(),
+            method: Method::default(),
+        }
+    }
+
+    pub fn with_board(mut self, board: &[Card]) -> Self {
+        self.board = board.to_vec();
+        self
+    }
+
+    /// Sets cards that are out of play but not on the board, such as folded hands.
+    pub fn with_dead_cards(mut self, dead_cards: &[Card]) -> Self {
+        self.dead_cards = dead_cards.to_vec();
+        self
+    }
+
+    pub fn with_method(mut self, method: Method) -> Self {
+        self.method = method;
+        self
+    }
+
+    /// Returns the equity of each range, in the order the ranges were given.
+    pub fn calculate(&self) -> Result<Vec<Equity>, EquityError> {
+        if self.ranges.len() < 2 {
+            return Err(EquityError::TooFewRanges(self.ranges.len()));
+        }
+        if self.board.len() > 5 {
+            return Err(EquityError::TooManyBoardCards(self.board.len()));
+        }
+        if let Method::MonteCarlo { samples: 0, .. } = self.method {
+            return Err(EquityError::NoSamples);
+        }
+        let mut known = 0u64;
+        for card in self.board.iter().chain(self.dead_cards.iter()) {
+            if known & card_bit(card.index() as u8) != 0 {
+                return Err(EquityError::DuplicateCard(*card));
+            }
+            known |= card_bit(card.index() as u8);
+        }
+        let players = self
+            .ranges
+            .iter()
+            .enumerate()
+            .map(|(player, range)| {
+                let combos: Vec<LiveCombo> = range
+                    .combos()
+                    .iter()
+                    .map(|combo| LiveCombo::new(&combo, range.frequency(&combo)))
+                    .filter(|combo| combo.mask & known == 0)
+                    .collect();
+                if combos.is_empty() {
+                    return Err(EquityError::EmptyRange(player));
+                }
+                Ok(combos)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let board: Vec<u8> = self.board.iter().map(|card| card.index() as u8).collect();
+        let mut tally = Tally::new(players.len());
+        match self.method {
+            Method::Exact => {
+                let mut hands = Vec::with_capacity(players.len());
+                deal_every_hand(&players, &board, known, 1.0, &mut hands, &mut tally);
+            }
+            Method::MonteCarlo { samples, seed } => {
+                deal_random_hands(&players, &board, known, samples, seed, &mut tally)?;
+            }
+        }
+        if tally.deals == 0.0 {
+            return Err(EquityError::NoDeal);
+        }
+        Ok(tally.equities())
+    }
+}
+
+/// A combo from a player's range, kept as card indices and a bitmask for fast dealing.
+#[derive(Debug, Clone, Copy)]
+struct LiveCombo {
+    cards: [u8; 2],
+    mask: u64,
+    frequency: f64,
+}
+
+impl LiveCombo {
+    fn new(combo: &Combo, frequency: f64) -> Self {
+        let cards = [
+            combo.first_card().index() as u8,
+            combo.second_card().index() as u8,
+        ];
+        LiveCombo {
+            cards,
+            mask: card_bit(cards[0]) | card_bit(cards[1]),
+            frequency,
+        }
+    }
+}
+
+fn card_bit(card: u8) -> u64 {
+    1 << card
+}
+
+/// Adds up the results of every deal, each weighted by how likely its hands are.
+struct Tally {
+    wins: Vec<f64>,
+    ties: Vec<f64>,
+    shares: Vec<f64>,
+    deals: f64,
+    /// Scratch space for the rank of each player's hand, reused across showdowns.
+    ranks: Vec<HandRank>,
+}
+
+impl Tally {
+    fn new(players: usize) -> Self {
+        Tally {
+            wins: vec![0.0; players],
+            ties: vec![0.0; players],
+            shares: vec![0.0; players],
+            deals: 0.0,
+            ranks: Vec::with_capacity(players),
+        }
+    }
+
+    /// Records a showdown between `hands` on a complete five-card `board`.
+    fn record(&mut self, hands: &[[u8; 2]], board: &[u8], weight: f64) {
+        let mut cards = [0u8; 7];
+        cards[..5].copy_from_slice(board);
+        self.ranks.clear();
+        let mut best = None;
+        let mut winners = 0;
+        for hand in hands {
+            cards[5..].copy_from_slice(hand);
+            let rank = evaluator::evaluate_indices(&cards);
+            if Some(rank) > best {
+                best = Some(rank);
+                winners = 1;
+            } else if Some(rank) == best {
+                winners += 1;
+            }
+            self.ranks.push(rank);
+        }
+        let Some(best) = best else {
+            return;
+        };
+        for (player, rank) in self.ranks.iter().enumerate() {
+            if *rank != best {
+                continue;
+            }
+            if winners == 1 {
+                self.wins[player] += weight;
+            } else {
+                self.ties[player] += weight;
+            }
+            self.shares[player] += weight / winners as f64;
+        }
+        self.deals += weight;
+    }
+
+    fn equities(&self) -> Vec<Equity> {
+        (0..self.wins.len())
+            .map(|player| Equity {
+                win: self.wins[player] / self.deals,
+                tie: self.ties[player] / self.deals,
+                equity: self.shares[player] / self.deals,
+            })
+            .collect()
+    }
+}
+
+/// Deals each player in turn every combo that doesn't clash with the cards already out, then
+/// runs out every board for each complete set of hands.
+fn deal_every_hand(
+    players: &[Vec<LiveCombo>],
+    board: &[u8],
+    used: u64,
+    weight: f64,
+    hands: &mut Vec<[u8; 2]>,
+    tally: &mut Tally,
+) {
+    let Some((combos, rest)) = players.split_first() else {
+        let deck: Vec<u8> = (0..52).filter(|card| used & card_bit(*card) == 0).collect();
+        let mut runout = board.to_vec();
+        deal_every_board(&deck, 5 - board.len(), &mut runout, &mut |board| {
+            tally.record(hands, board, weight)
+        });
+        return;
+    };
+    for combo in combos.iter().filter(|combo| combo.mask & used == 0) {
+        hands.push(combo.cards);
+        deal_every_hand(
+            rest,
+            board,
+            used | combo.mask,
+            weight * combo.frequency,
+            hands,
+            tally,
+        );
+        hands.pop();
+    }
+}
+
+/// Calls `record` with `board` completed by every choice of `count` cards from `deck`.
+fn deal_every_board(
+    deck: &[u8],
+    count: usize,
+    board: &mut Vec<u8>,
+    record: &mut impl FnMut(&[u8]),
+) {
+    if count == 0 {
+        record(board);
+        return;
+    }
+    for (index, card) in deck.iter().enumerate().take(deck.len() + 1 - count) {
+        board.push(*card);
+        deal_every_board(&deck[index + 1..], count - 1, board, record);
+        board.pop();
+    }
+}
+
+/// A player's combos along with the running total of their frequencies, for picking a combo
+/// in proportion to how often it's played.
+struct WeightedCombos<'a> {
+    combos: &'a [LiveCombo],
+    totals: Vec<f64>,
+}
+
+impl<'a> WeightedCombos<'a> {
+    fn new(combos: &'a [LiveCombo]) -> Self {
+        let totals = combos
+            .iter()
+            .scan(0.0, |total, combo| {
+                *total += combo.frequency;
+                Some(*total)
+            })
+            .collect();
+        WeightedCombos { combos, totals }
+    }
+
+    fn total(&self) -> f64 {
+        self.totals.last().copied().unwrap_or(0.0)
+    }
+
+    fn pick(&self, rng: &mut Rng) -> LiveCombo {
+        let target = rng.next_f64() * self.total();
+        let index = self.totals.partition_point(|total| *total <= target);
+        self.combos[index.min(self.combos.len() - 1)]
+    }
+}
+
+/// Returns whether every player can be dealt a combo they play that doesn't clash with the
+/// cards already `used` or with each other.
+fn can_deal(players: &[Vec<LiveCombo>], used: u64) -> bool {
+    let Some((combos, rest)) = players.split_first() else {
+        return true;
+    };
+    combos
+        .iter()
+        .filter(|combo| combo.frequency > 0.0 && combo.mask & used == 0)
+        .any(|combo| can_deal(rest, used | combo.mask))
+}
+
+/// Deals `samples` random sets of hands and boards. Each player's combo is picked in
+/// proportion to its frequency, and a set of hands that clashes is dealt again, so every set
+/// is as likely as it would be at the table.
+fn deal_random_hands(
+    players: &[Vec<LiveCombo>],
+    board: &[u8],
+    known: u64,
+    samples: usize,
+    seed: u64,
+    tally: &mut Tally,
+) -> Result<(), EquityError> {
+    let weighted: Vec<WeightedCombos> = players
+        .iter()
+        .map(|combos| WeightedCombos::new(combos))
+        .collect();
+    if weighted.iter().any(|combos| combos.total() <= 0.0) {
+        return Err(EquityError::NoDeal);
+    }
+    let mut rng = Rng::new(seed);
+    let mut hands = vec![[0u8; 2]; players.len()];
+    let mut deck: Vec<u8> = Vec::with_capacity(52);
+    let mut runout = [0u8; 5];
+    runout[..board.len()].copy_from_slice(board);
+    let mut checked = false;
+    for _ in 0..samples {
+        let mut failed_deals = 0;
+        let used = loop {
+            let mut used = known;
+            let dealt = weighted.iter().zip(hands.iter_mut()).all(|(combos, hand)| {
+                let combo = combos.pick(&mut rng);
+                if combo.mask & used != 0 {
+                    return false;
+                }
+                used |= combo.mask;
+                *hand = combo.cards;
+                true
+            });
+            if dealt {
+                break used;
+            }
+            failed_deals += 1;
+            // Clashes alone can make a deal rare, so only give up once it's certain that
+            // there's no deal to find.
+            if failed_deals == MAX_FAILED_DEALS && !checked {
+                if !can_deal(players, known) {
+                    return Err(EquityError::NoDeal);
+                }
+                checked = true;
+            }
+        };
+        deck.clear();
+        deck.extend((0..52).filter(|card| used & card_bit(*card) == 0));
+        for index in board.len()..5 {
+            let pick = index - board.len() + rng.below(deck.len() - (index - board.len()));
+            deck.swap(index - board.len(), pick);
+            runout[index] = deck[index - board.len()];
+        }
+        tally.record(&hands, &runout, 1.0);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    fn range(range: &str) -> WeightedComboRange {
+        let mut combo_range = WeightedComboRange::new();
+        for (element, frequency) in Parser::new(range).parse_weighted_element_list().unwrap() {
+            for combo in element.combos() {
+                combo_range.set(&combo, frequency);
+            }
+        }
+        combo_range
+    }
+
+    fn cards(cards: &str) -> Vec<Card> {
+        Parser::new(cards).parse_cards().unwrap()
+    }
+
+    #[test]
+    fn enumerates_every_runout() {
+        let equities = EquityCalculator::new(vec![range("AhAd"), range("KsKc")])
+            .with_board(&cards("Kd 7c 2h 3s"))
+            .with_method(Method::Exact)
+            .calculate()
+            .unwrap();
+        assert_eq!(equities[0].win, 2.0 / 44.0);
+        assert_eq!(equities[1].equity, 42.0 / 44.0);
+
+        let equities = EquityCalculator::new(vec![range("AsKs"), range("AdKd")])
+            .with_board(&cards("2c 3c 4h 7h"))
+            .with_method(Method::Exact)
+            .calculate()
+            .unwrap();
+        assert_eq!(
+            equities[0],
+            Equity {
+                win: 0.0,
+                tie: 1.0,
+                equity: 0.5
+            }
+        );
+
+        // The wheel beats trips, and a straight flush beats a full house.
+        let equities = EquityCalculator::new(vec![range("5c4c"), range("AhAd")])
+            .with_board(&cards("Ac 2c 3c Kh Ks"))
+            .with_method(Method::Exact)
+            .calculate()
+            .unwrap();
+        assert_eq!(equities[0].win, 1.0);
+    }
+
+    #[test]
+    fn samples_ranges() {
+        let calculator =
+            EquityCalculator::new(vec![range("AA"), range("KK")]).with_method(Method::MonteCarlo {
+                samples: 20_000,
+                seed: 7,
+            });
+        let equities = calculator.calculate().unwrap();
+        assert!((equities[0].equity - 0.82).abs() < 0.01);
+        assert!((equities[0].equity + equities[1].equity - 1.0).abs() < 1e-9);
+        assert_eq!(calculator.calculate().unwrap(), equities);
+
+        let equities = EquityCalculator::new(vec![range("QQ+"), range("AKs:0.5"), range("22")])
+            .with_dead_cards(&cards("As"))
+            .with_method(Method::MonteCarlo {
+                samples: 5_000,
+                seed: 1,
+            })
+            .calculate()
+            .unwrap();
+        let total: f64 = equities.iter().map(|equity| equity.equity).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+
+        // Tiny frequencies scale every deal alike, so they don't make dealing any harder.
+        let equities = EquityCalculator::new(vec![range("AA:0.00001"), range("KK")])
+            .with_method(Method::MonteCarlo {
+                samples: 2_000,
+                seed: 3,
+            })
+            .calculate()
+            .unwrap();
+        assert!((equities[0].equity - 0.82).abs() < 0.03);
+    }
+
+    #[test]
+    fn errs_on_impossible_deals() {
+        let calculate = |ranges: Vec<WeightedComboRange>, board: &str| {
+            EquityCalculator::new(ranges)
+                .with_board(&cards(board))
+                .with_method(Method::Exact)
+                .calculate()
+                .unwrap_err()
+        };
+        assert_eq!(
+            calculate(vec![range("AA")], ""),
+            EquityError::TooFewRanges(1)
+        );
+        assert_eq!(
+            calculate(vec![range("AhAd"), range("AhKh")], ""),
+            EquityError::NoDeal
+        );
+        let error = EquityCalculator::new(vec![range("AhAd"), range("AhKh")])
+            .calculate()
+            .unwrap_err();
+        assert_eq!(error, EquityError::NoDeal);
+        assert_eq!(
+            calculate(vec![range("KK"), range("AhAd")], "Ah 7c 2d"),
+            EquityError::EmptyRange(1)
+        );
+        assert_eq!(
+            calculate(vec![range("KK"), range("QQ")], "Ah 7c 2d 3s 4s 5s"),
+            EquityError::TooManyBoardCards(6)
+        );
+        let error = EquityCalculator::new(vec![range("KK"), range("QQ")])
+            .with_board(&cards("Ah 7c"))
+            .with_dead_cards(&cards("Ah"))
+            .calculate()
+            .unwrap_err();
+        assert_eq!(error, EquityError::DuplicateCard(cards("Ah")[0]));
+        let error = EquityCalculator::new(vec![range("KK"), range("QQ")])
+            .with_method(Method::MonteCarlo {
+                samples: 0,
+                seed: 0,
+            })
+            .calculate()
+            .unwrap_err();
+        assert_eq!(error, EquityError::NoSamples);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::cards::Card;
+use std::fmt::{self, Display};
+
+/// The number of sets of ranks, with one bit per rank from 0 for a two up to 12 for an ace.
+const RANK_MASKS: usize = 1 << 13;
+
+/// The top rank of the best straight in each set of ranks, plus one so that 0 can mean there
+/// isn't a straight. The wheel counts as five high.
+const STRAIGHTS: [u8; RANK_MASKS] = straights();
+
+/// Each set of ranks cut down to its five highest ranks, which is all that matters for a
+/// flush or a high card hand.
+const TOP_FIVE: [u16; RANK_MASKS] = top_five();
+
+const fn straights() -> [u8; RANK_MASKS] {
+    let mut table = [0; RANK_MASKS];
+    let mut mask = 0;
+    while mask < RANK_MASKS {
+        let mut high = 12;
+        while high >= 4 {
+            if (mask >> (high - 4)) & 0b11111 == 0b11111 {
+                table[mask] = high as u8 + 1;
+                break;
+            }
+            high -= 1;
+        }
+        if table[mask] == 0 && mask & 0b1_0000_0000_1111 == 0b1_0000_0000_1111 {
+            table[mask] = 4;
+        }
+        mask += 1;
+    }
+    table
+}
+
+const fn top_five() -> [u16; RANK_MASKS] {
+    let mut table = [0; RANK_MASKS];
+    let mut mask = 0;
+    while mask < RANK_MASKS {
+        let mut top = mask;
+        while top.count_ones() > 5 {
+            top &= top - 1;
+        }
+        table[mask] = top as u16;
+        mask += 1;
+    }
+    table
+}
+
+/// The kind of five-card hand, from weakest to strongest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+const CATEGORIES: [Category; 9] = [
+    Category::HighCard,
+    Category::Pair,
+    Category::TwoPair,
+    Category::ThreeOfAKind,
+    Category::Straight,
+    Category::Flush,
+    Category::FullHouse,
+    Category::FourOfAKind,
+    Category::StraightFlush,
+];
+
+impl Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Category::HighCard => write!(f, "High card"),
+            Category::Pair => write!(f, "Pair"),
+            Category::TwoPair => write!(f, "Two pair"),
+            Category::ThreeOfAKind => write!(f, "Three of a kind"),
+            Category::Straight => write!(f, "Straight"),
+            Category::Flush => write!(f, "Flush"),
+            Category::FullHouse => write!(f, "Full house"),
+            Category::FourOfAKind => write!(f, "Four of a kind"),
+            Category::StraightFlush => write!(f, "Straight flush"),
+        }
+    }
+}
+
+/// The strength of the best five-card hand among some cards. Stronger hands compare greater,
+/// and hands that would split the pot compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HandRank(u32);
+
+impl HandRank {
+    /// The category sits above the ranks that break ties within it.
+    const CATEGORY_SHIFT: u32 = 26;
+
+    fn new(category: Category, ranks: u32) -> Self {
+        HandRank((category as u32) << Self::CATEGORY_SHIFT | ranks)
+    }
+
+    pub fn category(&self) -> Category {
+        CATEGORIES[(self.0 >> Self::CATEGORY_SHIFT) as usize]
+    }
+}
+
+impl Display for HandRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.category())
+    }
+}
+
+/// Ranks the best five-card hand among 5, 6 or 7 distinct cards.
+pub fn evaluate(cards: &[Card]) -> HandRank {
+    assert!(
+        (5..=7).contains(&cards.len()),
+        "A hand is 5 to 7 cards, not {}",
+        cards.len()
+    );
+    let mut indices = [0u8; 7];
+    for (index, card) in indices.iter_mut().zip(cards) {
+        *index = card.index() as u8;
+    }
+    evaluate_indices(&indices[..cards.len()])
+}
+
+/// Ranks cards given as indices from `Card::index`, skipping the conversion from `Card` in
+/// hot loops such as equity calculations.
+pub(crate) fn evaluate_indices(cards: &[u8]) -> HandRank {
+    // Each mask holds the ranks seen at least that many times.
+    let (mut ones, mut twos, mut threes, mut fours) = (0u32, 0u32, 0u32, 0u32);
+    let mut suits = [0u32; 4];
+    for card in cards {
+        // `Card::index` counts down from the aces, while the masks count up from the twos.
+        let bit = 1 << (12 - card / 4);
+        suits[(card % 4) as usize] |= bit;
+        fours |= threes & bit;
+        threes |= twos & bit;
+        twos |= ones & bit;
+        ones |= bit;
+    }
+
+    // Seven cards can't hold a flush along with four of a kind or a full house, so a flush
+    // is always the best hand when there is one.
+    if let Some(flush) = suits.iter().find(|suit| suit.count_ones() >= 5) {
+        return match STRAIGHTS[*flush as usize] {
+            0 => HandRank::new(Category::Flush, TOP_FIVE[*flush as usize] as u32),
+            high => HandRank::new(Category::StraightFlush, high as u32),
+        };
+    }
+    if fours != 0 {
+        let quads = highest(fours);
+        let kicker = highest(ones & !(1 << quads));
+        return HandRank::new(Category::FourOfAKind, quads << 4 | kicker);
+    }
+    if threes != 0 {
+        let trips = highest(threes);
+        // The pair may be a second set of trips.
+        let pairs = twos & !(1 << trips);
+        if pairs != 0 {
+            return HandRank::new(Category::FullHouse, trips << 4 | highest(pairs));
+        }
+    }
+    match STRAIGHTS[ones as usize] {
+        0 => {}
+        high => return HandRank::new(Category::Straight, high as u32),
+    }
+    if threes != 0 {
+        let trips = highest(threes);
+        let kickers = top_ranks(ones & !(1 << trips), 2);
+        return HandRank::new(Category::ThreeOfAKind, trips << 13 | kickers);
+    }
+    if twos.count_ones() >= 2 {
+        let high = highest(twos);
+        let low = highest(twos & !(1 << high));
+        let kicker = top_ranks(ones & !(1 << high) & !(1 << low), 1);
+        return HandRank::new(Category::TwoPair, high << 17 | low << 13 | kicker);
+    }
+    if twos != 0 {
+        let pair = highest(twos);
+        let kickers = top_ranks(ones & !(1 << pair), 3);
+        return HandRank::new(Category::Pair, pair << 13 | kickers);
+    }
+    HandRank::new(Category::HighCard, TOP_FIVE[ones as usize] as u32)
+}
+
+/// Returns the highest rank in a non-empty set of ranks.
+fn highest(mask: u32) -> u32 {
+    31 - mask.leading_zeros()
+}
+
+/// Returns the `count` highest ranks in a set of ranks.
+fn top_ranks(mut mask: u32, count: u32) -> u32 {
+    while mask.count_ones() > count {
+        mask &= mask - 1;
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+    use crate::utils::Rng;
+
+    fn rank(cards: &str) -> HandRank {
+        evaluate(&Parser::new(cards).parse_cards().unwrap())
+    }
+
+    #[test]
+    fn ranks_categories() {
+        assert_eq!(rank("As Ks Qs Js Ts").category(), Category::StraightFlush);
+        assert_eq!(
+            rank("5d 4d 3d 2d Ad 9c").category(),
+            Category::StraightFlush
+        );
+        assert_eq!(rank("7c 7d 7h 7s 2c").category(), Category::FourOfAKind);
+        assert_eq!(rank("7c 7d 7h 2s 2c 2d").category(), Category::FullHouse);
+        assert_eq!(rank("Ah 9h 7h 4h 2h Kd Ks").category(), Category::Flush);
+        assert_eq!(rank("5c 4d 3h 2s Ac").category(), Category::Straight);
+        assert_eq!(rank("9c 9d 9h Ks 2c").category(), Category::ThreeOfAKind);
+        assert_eq!(rank("9c 9d Kh Ks 2c 2d Ac").category(), Category::TwoPair);
+        assert_eq!(rank("9c 9d Kh Qs 2c").category(), Category::Pair);
+        assert_eq!(rank("9c 8d Kh Qs 2c").category(), Category::HighCard);
+        assert_eq!(rank("9c 8d Kh Qs 2c").to_string(), "High card");
+    }
+
+    #[test]
+    fn orders_hands() {
+        assert!(rank("5c 4d 3h 2s Ac") < rank("6c 5d 4h 3s 2c"));
+        assert!(rank("5d 4d 3d 2d Ad") < rank("6d 5d 4d 3d 2d"));
+        assert!(rank("Ac Ad Kh Ks 3c") > rank("Ac Ad Qh Qs Kc"));
+        assert!(rank("Ac Ad Kh Ks 3c") < rank("Ac Ad Kh Ks 4c"));
+        assert!(rank("7c 7d 7h 2s 2c") > rank("6c 6d 6h As Ac"));
+        assert!(rank("Kh Jh 9h 7h 2h") > rank("Kd Jd 8d 7d 6d"));
+        assert_eq!(rank("Kh Jh 9h 7h 2h"), rank("Kc Jc 9c 7c 2c 3d"));
+        // The sixth and seventh best cards never count.
+        assert_eq!(rank("Ac Kd Qh Js 9c 3d 2h"), rank("As Kh Qd Jc 9d 4c 3s"));
+        assert_eq!(rank("Tc Td Th Ts Ac 2d"), rank("Tc Td Th Ts As Kc"));
+    }
+
+    #[test]
+    fn counts_every_five_card_hand() {
+        let mut counts = [0; 9];
+        for a in 0..52 {
+            for b in a + 1..52 {
+                for c in b + 1..52 {
+                    for d in c + 1..52 {
+                        for e in d + 1..52 {
+                            let rank = evaluate_indices(&[a, b, c, d, e]);
+                            counts[rank.category() as usize] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            counts,
+            [1_302_540, 1_098_240, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 40]
+        );
+    }
+
+    #[test]
+    fn takes_the_best_five_of_seven() {
+        let mut rng = Rng::new(25);
+        for _ in 0..1_000 {
+            let mut deck: Vec<u8> = (0..52).collect();
+            for index in 0..7 {
+                let pick = index + rng.below(52 - index);
+                deck.swap(index, pick);
+            }
+            let cards = &deck[..7];
+            let best = (0..7)
+                .flat_map(|skip| (skip + 1..7).map(move |other| (skip, other)))
+                .map(|(skip, other)| {
+                    let five: Vec<u8> = (0..7)
+                        .filter(|index| *index != skip && *index != other)
+                        .map(|index| cards[index])
+                        .collect();
+                    evaluate_indices(&five)
+                })
+                .max();
+            assert_eq!(Some(evaluate_indices(cards)), best);
+        }
+    }
+}
@@ -0,0 +1,408 @@
+use crate::lexer::Spelling;
+use crate::parser::{ErrorKind, ParseResult, Parser, RangeElement, VALID_HANDS};
+use crate::range::{ComboRange, Range, WeightedComboRange};
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// Range string conventions of common equity tools. They all list hand classes and combos
+/// without weights, with spans written from the highest hand down (`QQ-99`, `A5s-A2s`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// `AA, KK, AKs, AhKh`: entries separated by a comma and a space. Equilab copies and
+    /// pastes ranges in the same layout, so its ranges are read and written as this dialect.
+    PokerStove,
+    /// `AA,KK,AKs,AhKh`: entries separated by a bare comma.
+    Flopzilla,
+}
+
+impl Dialect {
+    fn separator(&self) -> &'static str {
+        match self {
+            Dialect::PokerStove => ", ",
+            Dialect::Flopzilla => ",",
+        }
+    }
+}
+
+/// The tokens of a range string that couldn't be translated, along with whatever could.
+#[derive(Debug, PartialEq)]
+pub struct TranslationError {
+    pub tokens: Vec<(String, ErrorKind)>,
+    pub partial: Box<ComboRange>,
+}
+
+impl Display for TranslationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tokens: Vec<String> = self
+            .tokens
+            .iter()
+            .map(|(token, error)| format!("`{}` ({})", token, error))
+            .collect();
+        write!(f, "Couldn't translate {}", tokens.join(", "))
+    }
+}
+
+impl Error for TranslationError {}
+
+/// Imports a range string written in `dialect`, read with `spelling`. Every token is checked,
+/// so the error lists all of the tokens that don't translate rather than just the first.
+pub fn import_range(
+    text: &str,
+    dialect: Dialect,
+    spelling: Spelling,
+) -> Result<ComboRange, TranslationError> {
+    let mut range = ComboRange::new();
+    let mut tokens: Vec<(String, ErrorKind)> = Vec::new();
+    for token in text.split(',').map(str::trim) {
+        if token.is_empty() && dialect == Dialect::Flopzilla {
+            // Flopzilla leaves a trailing comma on copied ranges.
+            continue;
+        }
+        match Parser::new(token).with_spelling(spelling).parse_combo_set() {
+            Ok(combos) => range = range.union(&combos),
+            Err(error) => tokens.push((token.to_string(), error.kind().clone())),
+        }
+    }
+    if tokens.is_empty() {
+        Ok(range)
+    } else {
+        Err(TranslationError {
+            tokens,
+            partial: Box::new(range),
+        })
+    }
+}
+
+/// Exports a range as a `dialect` range string, writing complete hand classes in the shortest
+/// notation and any other combos one by one.
+pub fn export_range(range: &ComboRange, dialect: Dialect) -> String {
+    let classes: Range = VALID_HANDS
+        .iter()
+        .filter(|hand| hand.combos().iter().all(|combo| range.contains(combo)))
+        .collect();
+    let mut tokens: Vec<String> = classes
+        .to_notation()
+        .split(", ")
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect();
+    tokens.extend(
+        range
+            .difference(&classes.combos())
+            .iter()
+            .map(|combo| combo.to_string()),
+    );
+    tokens.join(dialect.separator())
+}
+
+/// Exports a weighted range as a `dialect` range string. None of the dialects carry weights,
+/// so every combo must be played either always or never; the error lists any that aren't.
+pub fn export_weighted_range(
+    range: &WeightedComboRange,
+    dialect: Dialect,
+) -> Result<String, TranslationError> {
+    let mut tokens: Vec<(String, ErrorKind)> = Vec::new();
+    for hand in VALID_HANDS.iter() {
+        match range.hand_frequency(hand) {
+            Some(frequency) if frequency == 0.0 || frequency == 1.0 => {}
+            Some(frequency) => tokens.push((
+                format!("{}:{}", hand, frequency),
+                ErrorKind::InvalidFrequency(frequency.to_string()),
+            )),
+            None => {
+                for combo in hand.combos() {
+                    let frequency = range.frequency(&combo);
+                    if frequency != 0.0 && frequency != 1.0 {
+                        tokens.push((
+                            format!("{}:{}", combo, frequency),
+                            ErrorKind::InvalidFrequency(frequency.to_string()),
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    let full_combos: ComboRange = range
+        .combos()
+        .iter()
+        .filter(|combo| range.frequency(combo) == 1.0)
+        .collect();
+    if tokens.is_empty() {
+        Ok(export_range(&full_combos, dialect))
+    } else {
+        Err(TranslationError {
+            tokens,
+            partial: Box::new(full_combos),
+        })
+    }
+}
+
+/// Reads a range in the weighted format used by PioSolver and GTO+, e.g.
+/// `AA,AKs:0.5,AhQh:0.25`. Entries are hand classes or specific combos, a missing weight
+/// means 1.0, and when a combo is covered by several entries the last one wins.
+pub fn read_solver_range(text: &str) -> ParseResult<WeightedComboRange> {
+    read_notation(text, Spelling::Strict)
+}
+
+/// Reads a range written in pfrange's own notation, which the solver format is a subset of.
+pub fn read_notation(text: &str, spelling: Spelling) -> ParseResult<WeightedComboRange> {
+    let mut range = WeightedComboRange::new();
+    let elements = Parser::new(text)
+        .with_spelling(spelling)
+        .parse_weighted_element_list()?;
+    for (element, frequency) in elements {
+        match element {
+            RangeElement::Class(hand) => range.set_hand(&hand, frequency),
+            RangeElement::Combo(combo) => range.set(&combo, frequency),
+        }
+    }
+    Ok(range)
+}
+
+/// Writes a range in the PioSolver and GTO+ weighted format. Classes whose combos share a
+/// frequency are written as one entry and the rest combo by combo, and weights of 1.0 are
+/// left off.
+pub fn write_solver_range(range: &WeightedComboRange) -> String {
+    let mut entries: Vec<String> = Vec::new();
+    for hand in VALID_HANDS.iter() {
+        match range.hand_frequency(hand) {
+            Some(frequency) => {
+                if frequency > 0.0 {
+                    entries.push(solver_entry(hand, frequency));
+                }
+            }
+            None => {
+                for combo in hand.combos() {
+                    let frequency = range.frequency(&combo);
+                    if frequency > 0.0 {
+                        entries.push(solver_entry(&combo, frequency));
+                    }
+                }
+            }
+        }
+    }
+    entries.join(",")
+}
+
+/// Writes a range in pfrange's own notation, grouping hand classes that share a frequency,
+/// e.g. `QQ+, AKs, (AQs, KQs):0.5, AhJh:0.25`.
+pub fn write_notation(range: &WeightedComboRange) -> String {
+    let mut groups: Vec<(f64, Range)> = Vec::new();
+    let mut combos: Vec<String> = Vec::new();
+    for hand in VALID_HANDS.iter() {
+        match range.hand_frequency(hand) {
+            Some(frequency) if frequency > 0.0 => {
+                match groups
+                    .iter_mut()
+                    .find(|(existing, _)| *existing == frequency)
+                {
+                    Some((_, group)) => {
+                        group.insert(hand);
+                    }
+                    None => groups.push((frequency, [hand].into_iter().collect())),
+                }
+            }
+            Some(_) => {}
+            None => {
+                for combo in hand.combos() {
+                    let frequency = range.frequency(&combo);
+                    if frequency > 0.0 {
+                        combos.push(solver_entry(&combo, frequency));
+                    }
+                }
+            }
+        }
+    }
+    groups.sort_by(|(first, _), (second, _)| second.total_cmp(first));
+    let mut entries: Vec<String> = groups
+        .iter()
+        .map(|(frequency, group)| {
+            let notation = group.to_notation();
+            if *frequency == 1.0 {
+                notation
+            } else if group.len() == 1 || !notation.contains(", ") {
+                format!("{}:{}", notation, frequency)
+            } else {
+                format!("({}):{}", notation, frequency)
+            }
+        })
+        .collect();
+    entries.extend(combos);
+    entries.join(", ")
+}
+
+fn solver_entry(hand: &impl ToString, frequency: f64) -> String {
+    if frequency == 1.0 {
+        hand.to_string()
+    } else {
+        format!("{}:{}", hand.to_string(), frequency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Combo, Suit};
+    use crate::parser::CardType;
+
+    #[test]
+    fn reads_solver_ranges() {
+        let range = read_solver_range("AA:1,AKs:0.5,AhKh:0.25,QQ").unwrap();
+        let ace_king_hearts = Combo::new(
+            Card::new(CardType::Ace, Suit::Hearts),
+            Card::new(CardType::King, Suit::Hearts),
+        );
+        let ace_king_spades = Combo::new(
+            Card::new(CardType::Ace, Suit::Spades),
+            Card::new(CardType::King, Suit::Spades),
+        );
+        assert_eq!(range.frequency(&ace_king_hearts), 0.25);
+        assert_eq!(range.frequency(&ace_king_spades), 0.5);
+        assert_eq!(range.combo_count(), 6.0 + 1.75 + 6.0);
+        assert!(matches!(
+            read_solver_range("AA:2").unwrap_err().kind(),
+            ErrorKind::InvalidFrequency(_)
+        ));
+    }
+
+    #[test]
+    fn round_trips_solver_ranges() {
+        let text = "AA,AKs:0.5,AKo:0.125,AsQs:0.75,AhQh:0.75,AdQd:0.25,KK";
+        let range = read_solver_range(text).unwrap();
+        let written = write_solver_range(&range);
+        assert_eq!(
+            written,
+            "AA,AKs:0.5,AKo:0.125,AsQs:0.75,AhQh:0.75,AdQd:0.25,KK"
+        );
+        assert_eq!(read_solver_range(&written).unwrap(), range);
+    }
+
+    #[test]
+    fn round_trips_notation() {
+        let range = read_notation(
+            "QQ+, AKs, [50]AQs, KQs[/50], AhJh:0.25, 22:0.5",
+            Spelling::Strict,
+        )
+        .unwrap();
+        let written = write_notation(&range);
+        assert_eq!(written, "QQ+, AKs, (22, AQs, KQs):0.5, AhJh:0.25");
+        assert_eq!(read_notation(&written, Spelling::Strict).unwrap(), range);
+        let forgiving = read_notation(
+            "qq+, ace king suited, [50]aqs, kqs[/50], AhJh:0.25, 22:0.5",
+            Spelling::Forgiving,
+        );
+        assert_eq!(forgiving.unwrap(), range);
+    }
+
+    #[test]
+    fn imports_equity_tool_ranges() {
+        let stove = import_range(
+            "QQ-99, A5s-A2s, AKo, AhKh",
+            Dialect::PokerStove,
+            Spelling::Strict,
+        )
+        .unwrap();
+        assert_eq!(
+            stove,
+            ComboRange::parse("99-QQ, A2s-A5s, AKo, AhKh").unwrap()
+        );
+        let flopzilla = import_range(
+            "QQ-99,A5s-A2s,AKo,AhKh,",
+            Dialect::Flopzilla,
+            Spelling::Strict,
+        )
+        .unwrap();
+        assert_eq!(flopzilla, stove);
+        let forgiving = import_range(
+            "qq-99, a5s-a2s, ak off, A♥K♥",
+            Dialect::PokerStove,
+            Spelling::Forgiving,
+        );
+        assert_eq!(forgiving, Ok(stove));
+    }
+
+    #[test]
+    fn lists_untranslatable_tokens() {
+        let error = import_range(
+            "QQ+, AKs:0.5, KQs, JTz",
+            Dialect::PokerStove,
+            Spelling::Strict,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.tokens,
+            vec![
+                (
+                    "AKs:0.5".to_string(),
+                    ErrorKind::InvalidToken(":".to_string())
+                ),
+                ("JTz".to_string(), ErrorKind::InvalidToken("z".to_string())),
+            ]
+        );
+        assert_eq!(*error.partial, ComboRange::parse("QQ+, KQs").unwrap());
+        assert_eq!(
+            error.to_string(),
+            "Couldn't translate `AKs:0.5` (Unexpected token: :), `JTz` (Unexpected token: z)"
+        );
+    }
+
+    #[test]
+    fn exports_equity_tool_ranges() {
+        let range = ComboRange::parse("99-QQ, A2s-A5s, AKo, AhQh").unwrap();
+        assert_eq!(
+            export_range(&range, Dialect::PokerStove),
+            "QQ-99, A5s-A2s, AKo, AhQh"
+        );
+        assert_eq!(
+            export_range(&range, Dialect::Flopzilla),
+            "QQ-99,A5s-A2s,AKo,AhQh"
+        );
+        assert_eq!(
+            import_range(
+                &export_range(&range, Dialect::PokerStove),
+                Dialect::PokerStove,
+                Spelling::Strict
+            ),
+            Ok(range)
+        );
+
+        // Samples of what each tool writes for the same range.
+        let stove = "88+, A9s+, KTs+, QJs, AJo+, KQo";
+        let flopzilla = "88+,A9s+,KTs+,QJs,AJo+,KQo,";
+        let range = import_range(stove, Dialect::PokerStove, Spelling::Strict).unwrap();
+        assert_eq!(
+            import_range(flopzilla, Dialect::Flopzilla, Spelling::Strict),
+            Ok(range)
+        );
+        assert_eq!(export_range(&range, Dialect::PokerStove), stove);
+        assert_eq!(
+            export_range(&range, Dialect::Flopzilla),
+            flopzilla.trim_end_matches(',')
+        );
+
+        let weighted = read_solver_range("AA, AKs:0.5, AhQh:0.25").unwrap();
+        let error = export_weighted_range(&weighted, Dialect::PokerStove).unwrap_err();
+        assert_eq!(
+            error
+                .tokens
+                .iter()
+                .map(|(token, _)| token.as_str())
+                .collect::<Vec<_>>(),
+            vec!["AKs:0.5", "AhQh:0.25"]
+        );
+        assert_eq!(*error.partial, ComboRange::parse("AA").unwrap());
+    }
+
+    #[test]
+    fn converts_from_chart_notation() {
+        let chart = Parser::new("77+, [50]55-66, A5s[/50]")
+            .parse_weighted_range_list()
+            .unwrap();
+        let range = WeightedComboRange::from(&chart);
+        assert_eq!(
+            write_solver_range(&range),
+            "AA,A5s:0.5,KK,QQ,JJ,TT,99,88,77,66:0.5,55:0.5"
+        );
+        assert_eq!(range.to_weighted_range().combo_count(), chart.combo_count());
+    }
+}
@@ -0,0 +1,103 @@
+use crate::parser::{Hand, SuitedType, WeightedRange, CARD_TYPES};
+use crate::range::Range;
+
+/// The number of rows and columns in the hand matrix.
+pub const GRID_SIZE: usize = 13;
+
+const RESET: &str = "\x1b[0m";
+const EXCLUDED_COLOR: &str = "\x1b[90m";
+const INCLUDED_COLOR: &str = "\x1b[1;30;42m";
+/// 256-colour backgrounds from a dark to a bright green, used for partial frequencies.
+const SHADES: [u8; 4] = [22, 28, 34, 40];
+
+/// Returns the hand shown at `row` and `column` of the classic 13x13 matrix: pairs on the
+/// diagonal, suited hands above it and offsuit hands below it.
+pub fn grid_hand(row: usize, column: usize) -> Hand {
+    if row == column {
+        Hand::new(CARD_TYPES[row], CARD_TYPES[column], SuitedType::None)
+    } else if row < column {
+        Hand::new(CARD_TYPES[row], CARD_TYPES[column], SuitedType::Suited)
+    } else {
+        Hand::new(CARD_TYPES[column], CARD_TYPES[row], SuitedType::Offsuit)
+    }
+}
+
+/// Renders a range as a 13x13 grid, highlighting the included hands when `color` is set and
+/// otherwise replacing the excluded hands with dots.
+pub fn render_range(range: &Range, color: bool) -> String {
+    render(|hand| if range.contains(hand) { 1.0 } else { 0.0 }, color)
+}
+
+/// Renders a weighted range as a 13x13 grid. With `color` set, hands played some of the time
+/// are shaded by frequency; without it, they show their frequency as a percentage.
+pub fn render_weighted_range(range: &WeightedRange, color: bool) -> String {
+    render(|hand| range.frequency(hand), color)
+}
+
+fn render(frequency: impl Fn(&Hand) -> f64, color: bool) -> String {
+    let mut output = String::new();
+    for row in 0..GRID_SIZE {
+        for column in 0..GRID_SIZE {
+            let hand = grid_hand(row, column);
+            output.push_str(&render_cell(&hand, frequency(&hand), color));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+fn render_cell(hand: &Hand, frequency: f64, color: bool) -> String {
+    let label = format!(" {:<3} ", hand.to_string());
+    if color {
+        let cell_color = if frequency >= 1.0 {
+            INCLUDED_COLOR.to_string()
+        } else if frequency > 0.0 {
+            let shade = SHADES[((frequency * SHADES.len() as f64) as usize).min(SHADES.len() - 1)];
+            format!("\x1b[97;48;5;{}m", shade)
+        } else {
+            EXCLUDED_COLOR.to_string()
+        };
+        format!("{}{}{}", cell_color, label, RESET)
+    } else if frequency >= 1.0 {
+        label
+    } else if frequency > 0.0 {
+        format!(" {:>3.0}%", frequency * 100.0)
+    } else {
+        "  .  ".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{Parser, VALID_HANDS};
+
+    #[test]
+    fn lays_out_every_hand_once() {
+        let mut hands: Vec<Hand> = Vec::new();
+        for row in 0..GRID_SIZE {
+            for column in 0..GRID_SIZE {
+                hands.push(grid_hand(row, column));
+            }
+        }
+        assert_eq!(hands.len(), VALID_HANDS.len());
+        assert!(VALID_HANDS.iter().all(|hand| hands.contains(hand)));
+        assert_eq!(grid_hand(0, 1).to_string(), "AKs");
+        assert_eq!(grid_hand(1, 0).to_string(), "AKo");
+    }
+
+    #[test]
+    fn renders_plain_grid() {
+        let range = Range::parse("AA, AKs").unwrap();
+        let grid = render_range(&range, false);
+        let first_row = grid.lines().next().unwrap();
+        assert!(first_row.starts_with(" AA   AKs   .  "));
+        assert_eq!(grid.lines().count(), GRID_SIZE);
+
+        let weighted = Parser::new("AA, AKs:0.5")
+            .parse_weighted_range_list()
+            .unwrap();
+        let grid = render_weighted_range(&weighted, false);
+        assert!(grid.starts_with(" AA    50%  .  "));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::parser::CardType;
+
+/// How much the lexer tidies up a range string before the parser reads it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Spelling {
+    /// Only the canonical notation is accepted: `AKs`, `T9o`, `AhKh`.
+    #[default]
+    Strict,
+    /// Also accepts what people type by hand: any case (`aks`), `10` for a ten, words like
+    /// `ace king suited` or `KQ off`, a space before the suitedness (`ak s`) and suit symbols
+    /// (`A♠K♠`).
+    Forgiving,
+}
+
+/// Spelled-out ranks and the rank they stand for.
+const RANK_WORDS: [(&str, char); 14] = [
+    ("ace", 'A'),
+    ("king", 'K'),
+    ("queen", 'Q'),
+    ("jack", 'J'),
+    ("ten", 'T'),
+    ("nine", '9'),
+    ("eight", '8'),
+    ("seven", '7'),
+    ("six", '6'),
+    ("five", '5'),
+    ("four", '4'),
+    ("three", '3'),
+    ("two", '2'),
+    ("deuce", '2'),
+];
+
+/// Words for the suitedness of a hand, longest first where one ends another.
+const SUITEDNESS_WORDS: [(&str, char); 4] = [
+    ("offsuited", 'o'),
+    ("offsuit", 'o'),
+    ("suited", 's'),
+    ("off", 'o'),
+];
+
+/// The characters for the parser to read, each paired with the character offset in the
+/// original text that it came from. `offsets` has one extra entry marking the end of the
+/// text, so that an error at the end of the line still has somewhere to point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lexed {
+    pub characters: Vec<char>,
+    pub offsets: Vec<usize>,
+}
+
+/// Lexes `text` ready for the parser. Leading and trailing whitespace is dropped, and with
+/// forgiving spelling the text is rewritten into the canonical notation.
+pub fn lex(text: &str, spelling: Spelling) -> Lexed {
+    let start = text.chars().count() - text.trim_start().chars().count();
+    let source: Vec<char> = text.trim().chars().collect();
+    let tokens: Vec<(char, usize)> = match spelling {
+        Spelling::Strict => source.iter().copied().zip(start..).collect(),
+        Spelling::Forgiving => join_hands(&normalize(&source))
+            .into_iter()
+            .map(|(character, offset)| (character, start + offset))
+            .collect(),
+    };
+    let mut offsets: Vec<usize> = tokens.iter().map(|(_, offset)| *offset).collect();
+    offsets.push(start + source.len());
+    Lexed {
+        characters: tokens.into_iter().map(|(character, _)| character).collect(),
+        offsets,
+    }
+}
+
+/// Replaces words, `10`, suit symbols and stray case with the canonical characters. Text is
+/// read a word at a time, so keywords such as `top` or `connectors` are left alone and only
+/// words that spell out a hand are rewritten.
+fn normalize(source: &[char]) -> Vec<(char, usize)> {
+    let mut tokens: Vec<(char, usize)> = Vec::new();
+    let mut index = 0;
+    while index < source.len() {
+        let length = source[index..]
+            .iter()
+            .take_while(|character| is_word_character(**character))
+            .count();
+        if length == 0 {
+            let character = match source[index] {
+                '–' | '—' => '-',
+                other => other,
+            };
+            tokens.push((character, index));
+            index += 1;
+            continue;
+        }
+        // `suited` and `off` only stand for a suitedness straight after a hand, so that
+        // `suited connectors` keeps its keyword.
+        let after_hand = tokens
+            .iter()
+            .rev()
+            .find(|(character, _)| !character.is_whitespace())
+            .is_some_and(|(character, _)| is_rank(*character));
+        tokens.extend(normalize_word(source, index, length, after_hand));
+        index += length;
+    }
+    tokens
+}
+
+/// Rewrites the word of `length` characters at `start` if it spells out a rank, a
+/// suitedness or a hand, and otherwise returns it as it is.
+fn normalize_word(
+    source: &[char],
+    start: usize,
+    length: usize,
+    after_hand: bool,
+) -> Vec<(char, usize)> {
+    let word: String = source[start..start + length]
+        .iter()
+        .map(|character| character.to_ascii_lowercase())
+        .collect();
+    let find = |words: &[(&str, char)]| {
+        words
+            .iter()
+            .find(|(candidate, _)| *candidate == word)
+            .map(|(_, character)| vec![(*character, start)])
+    };
+    if let Some(rank) = find(&RANK_WORDS) {
+        return rank;
+    }
+    if after_hand {
+        if let Some(suitedness) = find(&SUITEDNESS_WORDS) {
+            return suitedness;
+        }
+    }
+    // A hand may have its suitedness spelled out on the end, as in `AKsuited`.
+    let (end, suitedness) = SUITEDNESS_WORDS
+        .iter()
+        .find_map(|(suffix, character)| {
+            let hand_length = word.strip_suffix(suffix)?.chars().count();
+            (hand_length > 0)
+                .then_some((start + hand_length, Some((*character, start + hand_length))))
+        })
+        .unwrap_or((start + length, None));
+    let mut hand: Vec<(char, usize)> = Vec::new();
+    let mut index = start;
+    while index < end {
+        if index + 2 <= end && is_ten(source, index) {
+            hand.push(('T', index));
+            index += 2;
+            continue;
+        }
+        let character = match source[index] {
+            'a' | 'k' | 'q' | 'j' | 't' => source[index].to_ascii_uppercase(),
+            'S' | 'H' | 'D' | 'C' | 'O' => source[index].to_ascii_lowercase(),
+            '♠' | '♤' => 's',
+            '♥' | '♡' => 'h',
+            '♦' | '♢' => 'd',
+            '♣' | '♧' => 'c',
+            other => other,
+        };
+        if !is_rank(character) && !matches!(character, 's' | 'h' | 'd' | 'c' | 'o') {
+            return (start..start + length)
+                .map(|index| (source[index], index))
+                .collect();
+        }
+        hand.push((character, index));
+        index += 1;
+    }
+    hand.extend(suitedness);
+    hand
+}
+
+/// Words are runs of letters and digits, along with suit symbols so that `A♠K♠` is one word.
+fn is_word_character(character: char) -> bool {
+    character.is_alphanumeric()
+        || matches!(character, '♠' | '♤' | '♥' | '♡' | '♦' | '♢' | '♣' | '♧')
+}
+
+fn is_rank(character: char) -> bool {
+    CardType::from_char(character).is_some() || matches!(character, 'x' | 'X')
+}
+
+/// A `10` is a ten unless it's part of a number, like the frequency in `AKs:0.10` or the
+/// percentages in `[10]` and `top 10%`.
+fn is_ten(source: &[char], index: usize) -> bool {
+    let is_number = |character: &char| character.is_ascii_digit() || *character == '.';
+    let after = source.get(index + 2);
+    let is_percentage = after == Some(&'%');
+    let before = index.checked_sub(1).map(|before| source[before]);
+    source[index..].starts_with(&['1', '0'])
+        && !after.is_some_and(is_number)
+        && !is_percentage
+        && !before.is_some_and(|before| is_number(&before) || matches!(before, ':' | '[' | '/'))
+}
+
+/// Drops the whitespace inside a hand that was spelled out in parts, as in `ace king` or
+/// `AK s`: a lone rank is joined to the rank after it, and two ranks to a lone `s` or `o`.
+fn join_hands(tokens: &[(char, usize)]) -> Vec<(char, usize)> {
+    let is_end = |character: Option<&(char, usize)>| match character {
+        Some((character, _)) => {
+            matches!(character, ',' | '+' | '-' | ':' | ')' | '[') || character.is_whitespace()
+        }
+        None => true,
+    };
+    let mut joined: Vec<(char, usize)> = Vec::new();
+    let mut index = 0;
+    while index < tokens.len() {
+        if !tokens[index].0.is_whitespace() {
+            joined.push(tokens[index]);
+            index += 1;
+            continue;
+        }
+        let mut next = index;
+        while next < tokens.len() && tokens[next].0.is_whitespace() {
+            next += 1;
+        }
+        let item: Vec<char> = joined
+            .iter()
+            .rev()
+            .take_while(|(character, _)| character.is_alphanumeric())
+            .map(|(character, _)| *character)
+            .collect();
+        let joins = match tokens.get(next) {
+            Some((character, _)) if item.len() == 1 && is_rank(item[0]) => is_rank(*character),
+            Some((character, _)) if item.len() == 2 && item.iter().all(|c| is_rank(*c)) => {
+                matches!(character, 's' | 'o') && is_end(tokens.get(next + 1))
+            }
+            _ => false,
+        };
+        if !joins {
+            joined.extend_from_slice(&tokens[index..next]);
+        }
+        index = next;
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forgiving(text: &str) -> String {
+        lex(text, Spelling::Forgiving).characters.iter().collect()
+    }
+
+    #[test]
+    fn normalizes_what_people_type() {
+        assert_eq!(forgiving("aks, kqo"), "AKs, KQo");
+        assert_eq!(forgiving("AKS, Ak"), "AKs, AK");
+        assert_eq!(forgiving("A10s+, J10o"), "ATs+, JTo");
+        assert_eq!(
+            forgiving("[10]AKsuited[/10], QJ:0.10"),
+            "[10]AKs[/10], QJ:0.10"
+        );
+        assert_eq!(forgiving("ace king suited, KQ off"), "AKs, KQo");
+        assert_eq!(forgiving("ak s, QQ o, A K"), "AKs, QQo, AK");
+        assert_eq!(forgiving("A♠K♠, 9♥8♥"), "AsKs, 9h8h");
+        assert_eq!(forgiving("AsKs Qh Jh"), "AsKs Qh Jh");
+        assert_eq!(forgiving("22–55"), "22-55");
+        assert_eq!(forgiving("top 10%, 10%"), "top 10%, 10%");
+        assert_eq!(forgiving("connectors 54s+"), "connectors 54s+");
+        assert_eq!(
+            forgiving("suited connectors 54s+, two-gappers"),
+            "suited connectors 54s+, 2-gappers"
+        );
+        assert_eq!(forgiving("kq offsuit, T9 suited"), "KQo, T9s");
+    }
+
+    #[test]
+    fn keeps_original_offsets() {
+        let lexed = lex("  aks", Spelling::Strict);
+        assert_eq!(lexed.characters, vec!['a', 'k', 's']);
+        assert_eq!(lexed.offsets, vec![2, 3, 4, 5]);
+
+        let lexed = lex("ten nine suited, x", Spelling::Forgiving);
+        assert_eq!(lexed.characters, vec!['T', '9', 's', ',', ' ', 'x']);
+        assert_eq!(lexed.offsets, vec![0, 4, 9, 15, 16, 17, 18]);
+    }
+}
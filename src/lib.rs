@@ -3,7 +3,16 @@ use std::error::Error;
 use std::fmt;
 use std::fs::OpenOptions;
 
+pub mod cards;
+pub mod chart;
 pub mod cli;
+pub mod equity;
+pub mod evaluator;
+pub mod formats;
+pub mod grid;
+pub mod lexer;
+pub mod range;
+pub mod ranking;
 pub mod utils;
 // const POSITIONS: [&str; 8] = ["EP1", "EP2", "EP3", "LJ", "HJ", "CO", "BTN", "SB"];
 pub enum Position {
@@ -19,28 +28,71 @@ pub enum Position {
 
 #[derive(Deserialize, Debug)]
 pub struct Schema {
-    raise: String,
-    raise_or_fold: String,
+    pub raise: String,
+    pub raise_or_fold: String,
 }
 
 #[derive(Deserialize, Debug)]
 pub struct PositionInput {
-    position: String,
-    schema: Schema,
+    pub position: String,
+    pub schema: Schema,
+}
+
+/// The ranges for one position in a range file laid out as a map, keyed by how often the
+/// hands are raised.
+#[derive(Deserialize, Debug)]
+struct FrequencyRanges {
+    #[serde(rename = "1.0", default)]
+    raise: String,
+    #[serde(rename = "0.5", default)]
+    raise_or_fold: String,
 }
 
-pub fn load_range_file<'a>(filename: &str) -> Result<Vec<PositionInput>, Box<dyn Error>> {
+/// Loads the positions in a JSON range file. The file is either a list of positions,
+///
+/// `[{"position": "utg", "schema": {"raise": "77+", "raise_or_fold": "55-66"}}]`
+///
+/// or a map from each position to its ranges keyed by frequency, with an optional title,
+/// as in `6-max rfi.toml`:
+///
+/// `{"title": "6-Max RFI", "utg": {"1.0": "77+", "0.5": "55-66"}}`
+pub fn load_range_file(filename: &str) -> Result<Vec<PositionInput>, Box<dyn Error>> {
     let range_file = OpenOptions::new().read(true).open(filename)?;
 
-    let position_inputs: Vec<PositionInput> = serde_json::from_reader(range_file)?;
-    Ok(position_inputs)
+    let value: serde_json::Value = serde_json::from_reader(range_file)?;
+    if value.is_array() {
+        let position_inputs: Vec<PositionInput> = serde_json::from_value(value)?;
+        return Ok(position_inputs);
+    }
+    let serde_json::Value::Object(mut positions) = value else {
+        return Err(format!("{} should hold a list or a map of positions", filename).into());
+    };
+    positions.remove("title");
+    positions
+        .into_iter()
+        .map(|(position, ranges)| {
+            let ranges: FrequencyRanges = serde_json::from_value(ranges)
+                .map_err(|e| format!("{}: {}: {}", filename, position, e))?;
+            Ok(PositionInput {
+                position,
+                schema: Schema {
+                    raise: ranges.raise,
+                    raise_or_fold: ranges.raise_or_fold,
+                },
+            })
+        })
+        .collect()
 }
 
 pub mod parser {
     use super::*;
-    use std::{char, fmt::Display};
+    use crate::cards::{self, Card, Combo, Suit, SUITS};
+    use crate::lexer::{self, Spelling};
+    use crate::range::{ComboRange, Connectors, Range, TOTAL_HANDS};
+    use crate::ranking::Ranking;
+    use std::{char, fmt::Display, mem, ops};
 
-    const VALID_HANDS: [Hand; 169] = [
+    pub const VALID_HANDS: [Hand; 169] = [
         Hand(CardType::Ace, CardType::Ace, SuitedType::None),
         Hand(CardType::Ace, CardType::King, SuitedType::Suited),
         Hand(CardType::Ace, CardType::King, SuitedType::Offsuit),
@@ -212,7 +264,7 @@ pub mod parser {
         Hand(CardType::Two, CardType::Two, SuitedType::None),
     ];
 
-    #[derive(PartialEq, PartialOrd, Debug, Clone)]
+    #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
     pub enum CardType {
         Ace = 14,
         King = 13,
@@ -229,6 +281,49 @@ pub mod parser {
         Two = 2,
     }
 
+    /// Every rank, from highest to lowest.
+    pub const CARD_TYPES: [CardType; 13] = [
+        CardType::Ace,
+        CardType::King,
+        CardType::Queen,
+        CardType::Jack,
+        CardType::Ten,
+        CardType::Nine,
+        CardType::Eight,
+        CardType::Seven,
+        CardType::Six,
+        CardType::Five,
+        CardType::Four,
+        CardType::Three,
+        CardType::Two,
+    ];
+
+    impl CardType {
+        /// Returns the position of this rank in `CARD_TYPES`, i.e. 0 for an ace and 12 for a two.
+        pub fn index(&self) -> usize {
+            CardType::Ace as usize - *self as usize
+        }
+
+        pub fn from_char(character: char) -> Option<Self> {
+            match character {
+                'A' => Some(CardType::Ace),
+                'K' => Some(CardType::King),
+                'Q' => Some(CardType::Queen),
+                'J' => Some(CardType::Jack),
+                'T' => Some(CardType::Ten),
+                '9' => Some(CardType::Nine),
+                '8' => Some(CardType::Eight),
+                '7' => Some(CardType::Seven),
+                '6' => Some(CardType::Six),
+                '5' => Some(CardType::Five),
+                '4' => Some(CardType::Four),
+                '3' => Some(CardType::Three),
+                '2' => Some(CardType::Two),
+                _ => None,
+            }
+        }
+    }
+
     impl Display for CardType {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             let card_display = match self {
@@ -297,14 +392,76 @@ pub mod parser {
             self.0 == self.1 && self.2 == SuitedType::None
         }
 
+        pub fn first_card(&self) -> CardType {
+            self.0
+        }
+
+        pub fn second_card(&self) -> CardType {
+            self.1
+        }
+
         pub fn suited_type(&self) -> &SuitedType {
             &self.2
         }
 
+        /// Returns how many ranks are missing between the two cards: 0 for a connector like
+        /// `76s`, 1 for a one-gapper like `75s`, or `None` for a pair.
+        pub fn gap(&self) -> Option<usize> {
+            (self.1.index() > self.0.index()).then(|| self.1.index() - self.0.index() - 1)
+        }
+
         pub fn from_hand(hand: &Hand, suited_type: SuitedType) -> Self {
             let cloned_hand = hand.to_owned();
             Hand(cloned_hand.0, cloned_hand.1, suited_type)
         }
+
+        /// Expands the hand class into its concrete combos: 6 for a pair, 4 for a suited hand
+        /// and 12 for an offsuit hand.
+        pub fn combos(&self) -> Vec<Combo> {
+            let mut combos = Vec::with_capacity(self.combo_count());
+            for (i, first_suit) in SUITS.iter().enumerate() {
+                for (j, second_suit) in SUITS.iter().enumerate() {
+                    let include = match self.2 {
+                        _ if self.0 == self.1 => i < j,
+                        SuitedType::Suited => i == j,
+                        SuitedType::Offsuit => i != j,
+                        SuitedType::None => true,
+                    };
+                    if include {
+                        combos.push(Combo::new(
+                            Card::new(self.0, *first_suit),
+                            Card::new(self.1, *second_suit),
+                        ));
+                    }
+                }
+            }
+            combos
+        }
+
+        /// Returns the position of this hand in `VALID_HANDS` without searching it, or `None`
+        /// if the hand isn't one of the 169 classes (e.g. `KA` or `AAs`).
+        pub fn index(&self) -> Option<usize> {
+            let first = self.0.index();
+            let second = self.1.index();
+            // Each first card's row holds its pair followed by a suited and offsuit hand for
+            // every lower kicker, so the row for card `i` starts at `i * (26 - i)`.
+            let row_start = first * (26 - first);
+            match self.2 {
+                SuitedType::None if first == second => Some(row_start),
+                SuitedType::Suited if first < second => Some(row_start + 2 * (second - first) - 1),
+                SuitedType::Offsuit if first < second => Some(row_start + 2 * (second - first)),
+                _ => None,
+            }
+        }
+
+        pub fn combo_count(&self) -> usize {
+            match self.2 {
+                _ if self.0 == self.1 => 6,
+                SuitedType::Suited => 4,
+                SuitedType::Offsuit => 12,
+                SuitedType::None => 16,
+            }
+        }
     }
 
     impl Display for Hand {
@@ -318,7 +475,7 @@ pub mod parser {
             if self.is_pair() && other.is_pair() {
                 // e.g. TT+ should return only pairs above or equal to TT (AA, KK, QQ, JJ, TT)
                 return self.0.partial_cmp(&other.0);
-            } else if self.matches_suited_type(&other.suited_type()) && self.0 == other.0 {
+            } else if self.matches_suited_type(other.suited_type()) && self.0 == other.0 {
                 // e.g. T2s+ should return only suited Tx hands (T9s, T8s, T7s, T6s, T5s, T4s, T3s, T2s)
                 return self.1.partial_cmp(&other.1);
             } else if self.matches_suited_type(&SuitedType::None)
@@ -332,38 +489,410 @@ pub mod parser {
         }
     }
 
-    #[derive(Debug, PartialEq, PartialOrd)]
-    pub enum ParseError {
+    /// A single item of a parsed range: either a whole hand class or one specific combo.
+    #[derive(Debug, PartialEq, Clone)]
+    pub enum RangeElement {
+        Class(Hand),
+        Combo(Combo),
+    }
+
+    impl RangeElement {
+        pub fn combos(&self) -> Vec<Combo> {
+            match self {
+                RangeElement::Class(hand) => hand.combos(),
+                RangeElement::Combo(combo) => vec![*combo],
+            }
+        }
+
+        fn into_hand(self) -> Option<Hand> {
+            match self {
+                RangeElement::Class(hand) => Some(hand),
+                RangeElement::Combo(_) => None,
+            }
+        }
+    }
+
+    impl Display for RangeElement {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                RangeElement::Class(hand) => write!(f, "{}", hand),
+                RangeElement::Combo(combo) => write!(f, "{}", combo),
+            }
+        }
+    }
+
+    /// A set of hands, each played at a frequency between 0.0 and 1.0, stored in one slot per
+    /// hand in `VALID_HANDS` order.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct WeightedRange {
+        frequencies: [Option<f64>; TOTAL_HANDS],
+    }
+
+    impl Default for WeightedRange {
+        fn default() -> Self {
+            WeightedRange {
+                frequencies: [None; TOTAL_HANDS],
+            }
+        }
+    }
+
+    impl WeightedRange {
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Adds `hand` at `frequency`, replacing the frequency if the hand is already present.
+        /// Hands that aren't one of the 169 classes in `VALID_HANDS` are never added.
+        pub fn insert(&mut self, hand: Hand, frequency: f64) {
+            if let Some(index) = hand.index() {
+                self.frequencies[index] = Some(frequency);
+            }
+        }
+
+        /// Returns whether `hand` is listed in the range, even at a frequency of 0.0.
+        pub fn contains(&self, hand: &Hand) -> bool {
+            hand.index()
+                .is_some_and(|index| self.frequencies[index].is_some())
+        }
+
+        /// Returns how often `hand` is played, or 0.0 if it isn't in the range.
+        pub fn frequency(&self, hand: &Hand) -> f64 {
+            hand.index()
+                .and_then(|index| self.frequencies[index])
+                .unwrap_or(0.0)
+        }
+
+        /// Returns the hands played at a non-zero frequency.
+        pub fn hands(&self) -> Vec<Hand> {
+            self.iter()
+                .filter(|(_, frequency)| *frequency > 0.0)
+                .map(|(hand, _)| hand.clone())
+                .collect()
+        }
+
+        /// Iterates over the hands in the range with their frequencies, strongest first in
+        /// `VALID_HANDS` order.
+        pub fn iter(&self) -> impl Iterator<Item = (&'static Hand, f64)> + '_ {
+            VALID_HANDS
+                .iter()
+                .zip(self.frequencies.iter())
+                .filter_map(|(hand, frequency)| frequency.map(|frequency| (hand, frequency)))
+        }
+
+        pub fn len(&self) -> usize {
+            self.frequencies.iter().flatten().count()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        /// Returns the number of combos in the range, each counted at its frequency.
+        pub fn combo_count(&self) -> f64 {
+            self.iter().fold(0.0, |total, (hand, frequency)| {
+                total + hand.combo_count() as f64 * frequency
+            })
+        }
+
+        /// Returns the share of all starting hands in the range, from 0.0 to 100.0.
+        pub fn percentage(&self) -> f64 {
+            self.combo_count() / cards::TOTAL_COMBOS as f64 * 100.0
+        }
+    }
+
+    /// What went wrong while parsing, without saying where.
+    #[derive(Debug, PartialEq, PartialOrd, Clone)]
+    pub enum ErrorKind {
         InvalidToken(String),
         InvalidRange(String),
+        InvalidFrequency(String),
+        InvalidCombo(String),
+        DuplicateCard(String),
+        SuitedPair(String),
+        ReversedRanks(String),
         EndOfLine,
     }
 
-    impl Display for ParseError {
+    impl Display for ErrorKind {
         fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             match self {
-                ParseError::InvalidToken(string) => write!(f, "Unexpected token: {}", string),
-                ParseError::InvalidRange(string) => write!(f, "Invalid range: {}", string),
-                ParseError::EndOfLine => write!(f, "Unexpected end of line"),
+                ErrorKind::InvalidToken(string) => write!(f, "Unexpected token: {}", string),
+                ErrorKind::InvalidRange(string) => write!(f, "Invalid range: {}", string),
+                ErrorKind::InvalidFrequency(string) => write!(f, "Invalid frequency: {}", string),
+                ErrorKind::InvalidCombo(string) => write!(f, "Invalid combo: {}", string),
+                ErrorKind::DuplicateCard(string) => write!(f, "Duplicate card: {}", string),
+                ErrorKind::SuitedPair(string) => {
+                    write!(f, "Pairs can't be suited or offsuit: {}", string)
+                }
+                ErrorKind::ReversedRanks(string) => {
+                    write!(f, "Higher rank must come first: {}", string)
+                }
+                ErrorKind::EndOfLine => write!(f, "Unexpected end of line"),
+            }
+        }
+    }
+
+    /// A parse error along with the range text it came from and the span of characters in
+    /// that text that caused it. Spans count characters rather than bytes, and an error at
+    /// the end of the text has an empty span just past its last character.
+    #[derive(Debug, PartialEq, Clone)]
+    pub struct ParseError {
+        kind: ErrorKind,
+        span: ops::Range<usize>,
+        source: String,
+    }
+
+    impl ParseError {
+        pub fn new(kind: ErrorKind, span: ops::Range<usize>, source: &str) -> Self {
+            ParseError {
+                kind,
+                span,
+                source: source.to_string(),
             }
         }
+
+        pub fn kind(&self) -> &ErrorKind {
+            &self.kind
+        }
+
+        /// Returns the character offset where the error starts.
+        pub fn offset(&self) -> usize {
+            self.span.start
+        }
+
+        pub fn span(&self) -> ops::Range<usize> {
+            self.span.clone()
+        }
+
+        /// Returns the range text that was being parsed.
+        pub fn source_text(&self) -> &str {
+            &self.source
+        }
+
+        /// Returns the 1-based line and column where the error starts.
+        pub fn line_column(&self) -> (usize, usize) {
+            let before: Vec<char> = self.source.chars().take(self.span.start).collect();
+            let line = before
+                .iter()
+                .filter(|character| **character == '\n')
+                .count()
+                + 1;
+            let column = before
+                .iter()
+                .rev()
+                .take_while(|character| **character != '\n')
+                .count()
+                + 1;
+            (line, column)
+        }
+
+        /// Renders the error the way rustc does, quoting the offending line of the range text
+        /// with carets under the span:
+        ///
+        /// ```text
+        /// error: Unexpected token: z
+        ///  --> 1:8
+        ///   |
+        /// 1 | QQ+, AKz
+        ///   |        ^
+        /// ```
+        pub fn render(&self) -> String {
+            let (line, column) = self.line_column();
+            let text = self.source.lines().nth(line - 1).unwrap_or("");
+            let line_length = text.chars().count();
+            let width = self
+                .span
+                .len()
+                .min(line_length.saturating_sub(column - 1))
+                .max(1);
+            let gutter = " ".repeat(line.to_string().len());
+            format!(
+                "error: {}\n{}--> {}:{}\n{} |\n{} | {}\n{} | {}{}",
+                self.kind,
+                gutter,
+                line,
+                column,
+                gutter,
+                line,
+                text,
+                gutter,
+                " ".repeat(column - 1),
+                "^".repeat(width)
+            )
+        }
     }
 
+    impl Display for ParseError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.kind)
+        }
+    }
+
+    impl std::error::Error for ParseError {}
+
     pub type ParseResult<T> = Result<T, ParseError>;
 
+    /// The ranges parsed so far in a group, in the order they were listed. The class and combo
+    /// bitsets mirror `elements` so that duplicates are found without a search.
+    #[derive(Default)]
+    struct Entries {
+        elements: Vec<(RangeElement, f64)>,
+        classes: Range,
+        combos: ComboRange,
+    }
+
+    #[derive(Clone, Copy)]
+    enum GroupEnd {
+        EndOfLine,
+        Parenthesis,
+        Block,
+    }
+
+    impl Entries {
+        /// Adds `element` at `frequency`, replacing the frequency if it was already listed.
+        fn insert(&mut self, element: RangeElement, frequency: f64) {
+            let is_new = match &element {
+                RangeElement::Class(hand) => self.classes.insert(hand),
+                RangeElement::Combo(combo) => self.combos.insert(combo),
+            };
+            if !is_new {
+                if let Some(entry) = self
+                    .elements
+                    .iter_mut()
+                    .find(|(existing, _)| *existing == element)
+                {
+                    entry.1 = frequency;
+                    return;
+                }
+            }
+            self.elements.push((element, frequency));
+        }
+
+        /// Removes `element`. Excluding a class also drops any of its combos, and excluding a
+        /// single combo from a listed class splits that class into its other combos.
+        fn exclude(&mut self, element: &RangeElement) {
+            match element {
+                RangeElement::Class(hand) => {
+                    self.classes.remove(hand);
+                    self.elements.retain(|(existing, _)| match existing {
+                        RangeElement::Class(existing_hand) => existing_hand != hand,
+                        RangeElement::Combo(combo) => combo.hand() != *hand,
+                    });
+                    for combo in hand.combos() {
+                        self.combos.remove(&combo);
+                    }
+                }
+                RangeElement::Combo(combo) => {
+                    let class = combo.hand();
+                    if self.classes.remove(&class) {
+                        let index = self
+                            .elements
+                            .iter()
+                            .position(|(existing, _)| {
+                                *existing == RangeElement::Class(class.clone())
+                            })
+                            .expect("classes mirrors elements");
+                        let (_, frequency) = self.elements.remove(index);
+                        let mut remaining: Vec<(RangeElement, f64)> = Vec::new();
+                        for other_combo in class.combos() {
+                            if other_combo == *combo {
+                                continue;
+                            }
+                            if self.combos.insert(&other_combo) {
+                                remaining.push((RangeElement::Combo(other_combo), frequency));
+                                continue;
+                            }
+                            // The combo is already listed on its own. If that was before the
+                            // class, the class's frequency replaces it as a relisting would.
+                            let element = RangeElement::Combo(other_combo);
+                            if let Some(position) = self
+                                .elements
+                                .iter()
+                                .position(|(existing, _)| *existing == element)
+                            {
+                                if position < index {
+                                    self.elements[position].1 = frequency;
+                                }
+                            }
+                        }
+                        self.elements.splice(index..index, remaining);
+                    }
+                    if self.combos.remove(combo) {
+                        self.elements.retain(|(existing, _)| existing != element);
+                    }
+                }
+            }
+        }
+
+        fn set_frequency(&mut self, frequency: f64) {
+            for entry in self.elements.iter_mut() {
+                entry.1 = frequency;
+            }
+        }
+    }
+
+    /// How the parser treats hands written out of the usual order. Strict validation rejects
+    /// reversed ranks like `KA`, while lenient validation quietly reads them as `AK`. Pairs
+    /// marked suited or offsuit, like `AAs`, are rejected either way.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum Validation {
+        #[default]
+        Strict,
+        Lenient,
+    }
+
     pub struct Parser {
+        source: String,
         characters: Vec<char>,
+        /// The offset in `source` of each of `characters`, plus one for the end of the text.
+        offsets: Vec<usize>,
         cursor: usize,
+        allow_combos: bool,
+        validation: Validation,
+        /// The order of hands that `top 15%` style ranges are taken from.
+        ranking: Ranking,
+        /// Set while recovering from errors, which are then collected in `errors` instead of
+        /// ending the parse.
+        recovering: bool,
+        errors: Vec<ParseError>,
     }
 
     impl Parser {
         pub fn new(string: &str) -> Self {
+            let lexed = lexer::lex(string, Spelling::Strict);
             Self {
-                characters: string.trim().chars().collect(),
+                source: string.to_string(),
+                characters: lexed.characters,
+                offsets: lexed.offsets,
                 cursor: 0,
+                allow_combos: true,
+                validation: Validation::default(),
+                ranking: Ranking::default(),
+                recovering: false,
+                errors: Vec::new(),
             }
         }
 
+        /// Sets how forgiving the parser is about spelling, relexing the range string. Call
+        /// this before parsing anything.
+        pub fn with_spelling(mut self, spelling: Spelling) -> Self {
+            let lexed = lexer::lex(&self.source, spelling);
+            self.characters = lexed.characters;
+            self.offsets = lexed.offsets;
+            self.cursor = 0;
+            self
+        }
+
+        pub fn with_validation(mut self, validation: Validation) -> Self {
+            self.validation = validation;
+            self
+        }
+
+        pub fn with_ranking(mut self, ranking: Ranking) -> Self {
+            self.ranking = ranking;
+            self
+        }
+
         pub fn peek(&self) -> Option<&char> {
             self.characters.get(self.cursor)
         }
@@ -379,7 +908,69 @@ pub mod parser {
         }
 
         pub fn is_eof(&self) -> bool {
-            self.characters.len() != 0 && self.characters.len() == self.cursor
+            !self.characters.is_empty() && self.characters.len() == self.cursor
+        }
+
+        pub fn skip_whitespace(&mut self) {
+            while let Some(character) = self.peek() {
+                if !character.is_whitespace() {
+                    break;
+                }
+                self.pop();
+            }
+        }
+
+        fn is_separator(character: &char) -> bool {
+            matches!(character, ',' | ':' | '[' | ')') || character.is_whitespace()
+        }
+
+        fn at_range_end(&self) -> bool {
+            match self.peek() {
+                Some(character) => Self::is_separator(character),
+                None => true,
+            }
+        }
+
+        /// Builds an error covering the characters from `start` up to the cursor, or just the
+        /// character under the cursor if nothing has been consumed since `start`.
+        fn error_from(&self, start: usize, kind: ErrorKind) -> ParseError {
+            let end = if self.cursor > start {
+                self.cursor
+            } else {
+                (start + 1).min(self.characters.len())
+            };
+            ParseError::new(kind, self.offsets[start]..self.offsets[end], &self.source)
+        }
+
+        /// Builds the error for whatever is under the cursor when it isn't what was expected.
+        fn unexpected(&self) -> ParseError {
+            let kind = match self.peek() {
+                Some(character) => ErrorKind::InvalidToken(character.to_string()),
+                None => ErrorKind::EndOfLine,
+            };
+            self.error_from(self.cursor, kind)
+        }
+
+        /// Records `error` and carries on when recovering, or hands it back otherwise.
+        fn recover(&mut self, error: ParseError) -> ParseResult<()> {
+            if !self.recovering {
+                return Err(error);
+            }
+            self.errors.push(error);
+            Ok(())
+        }
+
+        /// Skips the rest of a range that failed to parse, up to the next separator or the end
+        /// of the group.
+        fn skip_item(&mut self, end: GroupEnd) {
+            while !self.at_group_end(end) {
+                match self.peek() {
+                    Some(character) if *character != ',' && !character.is_whitespace() => {
+                        self.pop();
+                    }
+                    _ => break,
+                }
+            }
         }
 
         pub fn next_matches(&mut self, target: char) -> bool {
@@ -397,148 +988,809 @@ pub mod parser {
     }
 
     impl Parser {
-        pub fn parse_range(&mut self) -> ParseResult<Vec<Hand>> {
-            let hand = self.parse_hand()?;
-            match self.peek() {
-                Some('+') => Ok(self.get_gte_hands(&hand)),
-                Some('-') => {
-                    self.pop();
-                    let second_hand = self.parse_hand()?;
-                    match self.get_hands_between(&hand, &second_hand) {
-                        Ok(hands) => return Ok(hands),
-                        Err(err) => return Err(err),
+        /// Parses a full range string such as `"22+, A2s+, KTo+, QJs"` into a single list of
+        /// hands. Ranges may be separated by commas and any amount of whitespace, and hands
+        /// that appear in more than one range are only returned once.
+        pub fn parse_range_list(&mut self) -> ParseResult<Vec<Hand>> {
+            self.allow_combos = false;
+            Ok(self
+                .parse_entries(false)?
+                .elements
+                .into_iter()
+                .filter_map(|(element, _)| element.into_hand())
+                .collect())
+        }
+
+        /// Parses a range string that may carry frequencies, either per range with a colon
+        /// suffix (`AKs:0.5`, `A2s+:0.25`) or for a whole block of ranges with solver-style
+        /// percentage brackets (`[50]QQ, JJ[/50]`). Ranges without a frequency are weighted 1.0,
+        /// and when a hand is listed more than once the last frequency wins.
+        pub fn parse_weighted_range_list(&mut self) -> ParseResult<WeightedRange> {
+            self.allow_combos = false;
+            let mut range = WeightedRange::new();
+            for (element, frequency) in self.parse_entries(true)?.elements {
+                if let Some(hand) = element.into_hand() {
+                    range.insert(hand, frequency);
+                }
+            }
+            Ok(range)
+        }
+
+        /// Parses a range string that mixes hand classes with explicit combos, e.g.
+        /// `"QQ+, AhKh, AsKs, KQs"`.
+        pub fn parse_element_list(&mut self) -> ParseResult<Vec<RangeElement>> {
+            Ok(self
+                .parse_entries(false)?
+                .elements
+                .into_iter()
+                .map(|(element, _)| element)
+                .collect())
+        }
+
+        /// Parses a range string that mixes hand classes and explicit combos, each with an
+        /// optional frequency, e.g. `"AA, AKs:0.5, AhQh:0.25"`.
+        pub fn parse_weighted_element_list(&mut self) -> ParseResult<Vec<(RangeElement, f64)>> {
+            Ok(self.parse_entries(true)?.elements)
+        }
+
+        /// Parses a range string like `parse_element_list` and expands every hand class into
+        /// its combos, returning each combo only once.
+        pub fn parse_combo_list(&mut self) -> ParseResult<Vec<Combo>> {
+            let mut seen = ComboRange::new();
+            let mut combos: Vec<Combo> = Vec::new();
+            for element in self.parse_element_list()? {
+                for combo in element.combos() {
+                    if seen.insert(&combo) {
+                        combos.push(combo);
                     }
                 }
-                Some(other_character) => Err(ParseError::InvalidToken(other_character.to_string())),
-                None => {
-                    if !hand.is_pair() && hand.matches_suited_type(&SuitedType::None) {
-                        Ok(vec![
-                            Hand::from_hand(&hand, SuitedType::Suited),
-                            Hand::from_hand(&hand, SuitedType::Offsuit),
-                        ])
-                    } else {
-                        Ok(vec![hand])
+            }
+            Ok(combos)
+        }
+
+        /// Parses a range string of hand classes straight into a `Range` bitset.
+        pub fn parse_range_set(&mut self) -> ParseResult<Range> {
+            self.allow_combos = false;
+            Ok(self.parse_entries(false)?.classes)
+        }
+
+        /// Parses a range string of hand classes and explicit combos straight into a
+        /// `ComboRange` bitset.
+        pub fn parse_combo_set(&mut self) -> ParseResult<ComboRange> {
+            let entries = self.parse_entries(false)?;
+            Ok(entries.combos.union(&entries.classes.combos()))
+        }
+
+        /// Parses like `parse_range_list`, but rather than stopping at the first error it
+        /// records the error, skips to the next separator and carries on. Returns every hand
+        /// that could be parsed along with every error, so all of the problems in a range can
+        /// be shown at once.
+        pub fn recover_range_list(&mut self) -> (Vec<Hand>, Vec<ParseError>) {
+            self.recover_with(Self::parse_range_list)
+        }
+
+        /// Parses like `parse_weighted_range_list`, recovering from errors the way
+        /// `recover_range_list` does.
+        pub fn recover_weighted_range_list(&mut self) -> (WeightedRange, Vec<ParseError>) {
+            self.recover_with(Self::parse_weighted_range_list)
+        }
+
+        fn recover_with<T: Default>(
+            &mut self,
+            parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+        ) -> (T, Vec<ParseError>) {
+            self.recovering = true;
+            let value = parse(self).unwrap_or_else(|error| {
+                self.errors.push(error);
+                T::default()
+            });
+            self.recovering = false;
+            (value, mem::take(&mut self.errors))
+        }
+
+        fn parse_entries(&mut self, allow_weights: bool) -> ParseResult<Entries> {
+            self.parse_group(1.0, allow_weights, GroupEnd::EndOfLine)
+        }
+
+        /// Parses ranges up to `end`. Ranges are separated by commas or whitespace, and a range
+        /// prefixed with `!` or a standalone `-` is removed from the ranges listed before it,
+        /// so `ATs+, KQs, !AKs` and `A2s+ - A5s` are both set differences, while in
+        /// `!AKs, AKs` the later `AKs` is added back. A standalone `-` between two hands that
+        /// make a span, as in `QQ - 99`, is read as that span.
+        fn parse_group(
+            &mut self,
+            frequency: f64,
+            allow_weights: bool,
+            end: GroupEnd,
+        ) -> ParseResult<Entries> {
+            let mut entries = Entries::default();
+            self.skip_whitespace();
+            loop {
+                // An unclosed group runs to the end of the line, where the caller reports the
+                // missing closing bracket.
+                if self.at_group_end(end) || self.peek().is_none() {
+                    break;
+                }
+                let negated = self.next_matches('!') || self.next_matches('-');
+                if negated {
+                    self.skip_whitespace();
+                }
+                match self.parse_item(frequency, allow_weights) {
+                    Ok(item) if negated => {
+                        for (element, _) in item.elements {
+                            entries.exclude(&element);
+                        }
+                    }
+                    Ok(item) => {
+                        for (element, frequency) in item.elements {
+                            entries.insert(element, frequency);
+                        }
+                    }
+                    Err(error) => {
+                        self.recover(error)?;
+                        self.skip_item(end);
+                    }
+                }
+
+                let cursor = self.cursor;
+                self.skip_whitespace();
+                if self.cursor == cursor
+                    && !self.at_group_end(end)
+                    && !matches!(self.peek(), None | Some(','))
+                {
+                    self.recover(self.unexpected())?;
+                    self.skip_item(end);
+                    self.skip_whitespace();
+                }
+                if self.next_matches(',') {
+                    self.skip_whitespace();
+                    if self.at_group_end(end) {
+                        self.recover(self.unexpected())?;
                     }
                 }
             }
+            Ok(entries)
         }
 
-        pub fn parse_hand(&mut self) -> ParseResult<Hand> {
-            let first_card = self.parse_card()?;
-            let second_card = self.parse_card()?;
-            let suited_type = if self.next_matches('s') {
-                SuitedType::Suited
-            } else if self.next_matches('o') {
-                SuitedType::Offsuit
+        /// Parses a single range, combo, `(...)` group or `[50]...[/50]` block along with an
+        /// optional `:0.5` frequency suffix.
+        fn parse_item(&mut self, frequency: f64, allow_weights: bool) -> ParseResult<Entries> {
+            let mut entries = if self.next_matches('(') {
+                let entries = self.parse_group(frequency, allow_weights, GroupEnd::Parenthesis)?;
+                self.expect(')')?;
+                entries
+            } else if allow_weights && self.peek() == Some(&'[') {
+                self.parse_weighted_block()?
             } else {
-                SuitedType::None
+                let mut entries = Entries::default();
+                for element in self.parse_range_elements()? {
+                    entries.insert(element, frequency);
+                }
+                entries
             };
-            let result = Hand(first_card, second_card, suited_type);
-            Ok(result)
+            if allow_weights && self.next_matches(':') {
+                let start = self.cursor;
+                let number = self.parse_number()?;
+                if !(0.0..=1.0).contains(&number) {
+                    return Err(
+                        self.error_from(start, ErrorKind::InvalidFrequency(number.to_string()))
+                    );
+                }
+                entries.set_frequency(number);
+            }
+            Ok(entries)
         }
 
-        pub fn parse_card(&mut self) -> ParseResult<CardType> {
-            if self.next_matches('A') {
-                Ok(CardType::Ace)
-            } else if self.next_matches('K') {
-                Ok(CardType::King)
-            } else if self.next_matches('Q') {
-                Ok(CardType::Queen)
-            } else if self.next_matches('J') {
-                Ok(CardType::Jack)
-            } else if self.next_matches('T') {
-                Ok(CardType::Ten)
-            } else if self.next_matches('9') {
-                Ok(CardType::Nine)
-            } else if self.next_matches('8') {
-                Ok(CardType::Eight)
-            } else if self.next_matches('7') {
-                Ok(CardType::Seven)
-            } else if self.next_matches('6') {
-                Ok(CardType::Six)
-            } else if self.next_matches('5') {
-                Ok(CardType::Five)
-            } else if self.next_matches('4') {
-                Ok(CardType::Four)
-            } else if self.next_matches('3') {
-                Ok(CardType::Three)
-            } else if self.next_matches('2') {
-                Ok(CardType::Two)
-            } else {
-                match self.peek() {
-                    Some(character) => Err(ParseError::InvalidToken(character.to_string())),
-                    None => Err(ParseError::EndOfLine),
+        /// Parses a `[50]...[/50]` block, applying its percentage to every range inside it.
+        fn parse_weighted_block(&mut self) -> ParseResult<Entries> {
+            let start = self.cursor;
+            let percentage = self.parse_block_tag(false)?;
+            if !(0.0..=100.0).contains(&percentage) {
+                return Err(
+                    self.error_from(start, ErrorKind::InvalidFrequency(percentage.to_string()))
+                );
+            }
+            let entries = self.parse_group(percentage / 100.0, true, GroupEnd::Block)?;
+            let closing_start = self.cursor;
+            let closing_percentage = self.parse_block_tag(true)?;
+            if closing_percentage != percentage {
+                return Err(self.error_from(
+                    closing_start,
+                    ErrorKind::InvalidFrequency(format!(
+                        "[{}] closed by [/{}]",
+                        percentage, closing_percentage
+                    )),
+                ));
+            }
+            Ok(entries)
+        }
+
+        fn at_group_end(&self, end: GroupEnd) -> bool {
+            match end {
+                GroupEnd::EndOfLine => self.peek().is_none(),
+                GroupEnd::Parenthesis => self.peek() == Some(&')'),
+                GroupEnd::Block => {
+                    self.peek() == Some(&'[') && self.characters.get(self.cursor + 1) == Some(&'/')
                 }
             }
         }
 
-        fn get_gte_hands(&self, hand: &Hand) -> Vec<Hand> {
-            if hand.matches_suited_type(&SuitedType::None) && !hand.is_pair() {
-                let suited_hand = Hand::from_hand(hand, SuitedType::Suited);
-                let offsuit_hand = Hand::from_hand(hand, SuitedType::Offsuit);
-                return Vec::from(VALID_HANDS)
-                    .into_iter()
-                    .filter(|valid_hand| valid_hand >= &suited_hand || valid_hand >= &offsuit_hand)
-                    .collect();
+        fn parse_block_tag(&mut self, closing: bool) -> ParseResult<f64> {
+            self.expect('[')?;
+            if closing {
+                self.expect('/')?;
             }
-            Vec::from(VALID_HANDS)
-                .into_iter()
-                .filter(|valid_hand| valid_hand >= hand)
-                .collect()
+            let number = self.parse_number()?;
+            self.expect(']')?;
+            Ok(number)
         }
 
-        // fn get_lte_hands(&self, hand: &Hand) -> Vec<Hand> {
-        //     if hand.matches_suited_type(&SuitedType::None) && !hand.is_pair() {
-        //         let hand_suited = Hand::from_hand(hand, SuitedType::Suited);
-        //         let hand_offsuit = Hand::from_hand(hand, SuitedType::Offsuit);
-        //         return Vec::from(VALID_HANDS)
-        //             .into_iter()
-        //             .filter(|valid_hand| valid_hand <= &hand_suited || valid_hand <= &hand_offsuit)
-        //             .collect();
-        //     }
-        //     Vec::from(VALID_HANDS)
-        //         .into_iter()
-        //         .filter(|valid_hand| valid_hand <= hand && valid_hand.matches_suited_type(&hand.2))
-        //         .collect()
-        // }
+        fn parse_number(&mut self) -> ParseResult<f64> {
+            let start = self.cursor;
+            let mut digits = String::new();
+            while let Some(character) = self.peek() {
+                if !character.is_ascii_digit() && *character != '.' {
+                    break;
+                }
+                digits.push(*character);
+                self.pop();
+            }
+            if digits.is_empty() {
+                return Err(self.unexpected());
+            }
+            digits
+                .parse::<f64>()
+                .map_err(|_| self.error_from(start, ErrorKind::InvalidFrequency(digits.clone())))
+        }
 
-        fn get_hands_between(
-            &self,
-            first_hand: &Hand,
-            second_hand: &Hand,
-        ) -> ParseResult<Vec<Hand>> {
-            if first_hand.matches_suited_type(&SuitedType::None) && !first_hand.is_pair() {
-                if second_hand.matches_suited_type(&SuitedType::None) && !second_hand.is_pair() {
-                    return Err(ParseError::InvalidRange("Can't specify a range between an offsuit (or pair) hand and a suited pair.".to_string()));
-                }
-                let first_hand_suited = Hand::from_hand(first_hand, SuitedType::Suited);
-                let first_hand_offsuit = Hand::from_hand(first_hand, SuitedType::Offsuit);
-                let second_hand_suited = Hand::from_hand(second_hand, SuitedType::Suited);
-                let second_hand_offsuit = Hand::from_hand(second_hand, SuitedType::Offsuit);
-                return Ok(Vec::from(VALID_HANDS)
-                    .into_iter()
-                    .filter(|valid_hand| {
-                        (valid_hand >= &first_hand_suited && valid_hand <= &second_hand_suited)
-                            || valid_hand >= &first_hand_offsuit
-                                && valid_hand <= &second_hand_offsuit
-                    })
-                    .collect());
-            }
-            Ok(Vec::from(VALID_HANDS)
-                .into_iter()
-                .filter(|valid_hand| {
-                    (valid_hand >= first_hand && valid_hand.matches_suited_type(&first_hand.2))
-                        && (valid_hand <= second_hand
-                            && valid_hand.matches_suited_type(&second_hand.2))
-                })
-                .collect())
+        fn expect(&mut self, target: char) -> ParseResult<()> {
+            if self.next_matches(target) {
+                return Ok(());
+            }
+            Err(self.unexpected())
         }
-    }
 
-    pub enum Error {
-        Character(usize),
-        EndOfLine,
-    }
+        /// Parses a single range like `parse_range`, or a single explicit combo.
+        pub fn parse_range_elements(&mut self) -> ParseResult<Vec<RangeElement>> {
+            if !self.next_is_combo() {
+                let hands = self.parse_range()?;
+                return Ok(hands.into_iter().map(RangeElement::Class).collect());
+            }
+            let start = self.cursor;
+            let combo = self.parse_combo()?;
+            match self.peek() {
+                Some(character) if !Self::is_separator(character) => Err(self.unexpected()),
+                _ if !self.allow_combos => Err(self.error_from(
+                    start,
+                    ErrorKind::InvalidRange(format!(
+                        "{} is a specific combo, not a hand class",
+                        combo
+                    )),
+                )),
+                _ => Ok(vec![RangeElement::Combo(combo)]),
+            }
+        }
+
+        pub fn parse_range(&mut self) -> ParseResult<Vec<Hand>> {
+            if self.next_is_percentile() {
+                return self.parse_percentile_range();
+            }
+            if self.next_is_wildcard() {
+                return self.parse_wildcard_range();
+            }
+            if let Some((gap, suited_type)) = self.parse_family() {
+                return self.parse_connector_range(gap, suited_type);
+            }
+            let start = self.cursor;
+            let hand = self.parse_hand()?;
+            match self.peek() {
+                Some('+') => {
+                    self.pop();
+                    Ok(self.get_gte_hands(&hand).hands())
+                }
+                Some('-') => {
+                    self.pop();
+                    if self.at_range_end() {
+                        // e.g. T9s- should return T9s and every suited Tx hand below it
+                        return Ok(self.get_lte_hands(&hand).hands());
+                    }
+                    let second_hand = self.parse_hand()?;
+                    self.get_hands_between(&hand, &second_hand)
+                        .map(|range| range.hands())
+                        .map_err(|kind| self.error_from(start, kind))
+                }
+                Some(other_character) if !Self::is_separator(other_character) => {
+                    Err(self.unexpected())
+                }
+                _ => {
+                    if let Some(hands) = self.parse_spaced_span(&hand) {
+                        return Ok(hands);
+                    }
+                    if !hand.is_pair() && hand.matches_suited_type(&SuitedType::None) {
+                        Ok(vec![
+                            Hand::from_hand(&hand, SuitedType::Suited),
+                            Hand::from_hand(&hand, SuitedType::Offsuit),
+                        ])
+                    } else {
+                        Ok(vec![hand])
+                    }
+                }
+            }
+        }
+
+        /// Reads a span typed with spaces around the dash, like `QQ - 99` or `A5s - A2s`, when
+        /// both ends are pairs or share a first card and suitedness. Anything else after a
+        /// standalone `-`, as in `A2s+ - A5s`, is left to be read as an exclusion.
+        fn parse_spaced_span(&mut self, hand: &Hand) -> Option<Vec<Hand>> {
+            let cursor = self.cursor;
+            self.skip_whitespace();
+            let spaced = self.cursor > cursor && self.next_matches('-') && {
+                let dash = self.cursor;
+                self.skip_whitespace();
+                self.cursor > dash
+            };
+            let hands = spaced
+                .then(|| self.parse_hand().ok())
+                .flatten()
+                .filter(|second_hand| {
+                    self.at_range_end()
+                        && second_hand.is_pair() == hand.is_pair()
+                        && (hand.is_pair() || (hand.0 == second_hand.0 && hand.2 == second_hand.2))
+                })
+                .and_then(|second_hand| self.get_hands_between(hand, &second_hand).ok())
+                .map(|range| range.hands());
+            if hands.is_none() {
+                self.cursor = cursor;
+            }
+            hands
+        }
+
+        pub fn parse_hand(&mut self) -> ParseResult<Hand> {
+            let start = self.cursor;
+            let mut first_card = self.parse_card()?;
+            let mut second_card = self.parse_card()?;
+            let suited_type = if self.next_matches('s') {
+                SuitedType::Suited
+            } else if self.next_matches('o') {
+                SuitedType::Offsuit
+            } else {
+                SuitedType::None
+            };
+            let text: String = self.characters[start..self.cursor].iter().collect();
+            if first_card == second_card && suited_type != SuitedType::None {
+                return Err(self.error_from(start, ErrorKind::SuitedPair(text)));
+            }
+            if first_card < second_card {
+                if self.validation == Validation::Strict {
+                    return Err(self.error_from(start, ErrorKind::ReversedRanks(text)));
+                }
+                mem::swap(&mut first_card, &mut second_card);
+            }
+            Ok(Hand(first_card, second_card, suited_type))
+        }
+
+        pub fn parse_card(&mut self) -> ParseResult<CardType> {
+            match self.peek() {
+                Some(character) => match CardType::from_char(*character) {
+                    Some(card) => {
+                        self.pop();
+                        Ok(card)
+                    }
+                    None => Err(self.unexpected()),
+                },
+                None => Err(self.unexpected()),
+            }
+        }
+
+        pub fn parse_suit(&mut self) -> ParseResult<Suit> {
+            match self.peek() {
+                Some(character) => match Suit::from_char(*character) {
+                    Some(suit) => {
+                        self.pop();
+                        Ok(suit)
+                    }
+                    None => Err(self.unexpected()),
+                },
+                None => Err(self.unexpected()),
+            }
+        }
+
+        /// Parses a list of known cards such as a board (`Ah7c2d`) or hole cards (`As Ks`).
+        /// Cards may be written back to back or separated by commas and whitespace.
+        pub fn parse_cards(&mut self) -> ParseResult<Vec<Card>> {
+            let mut cards: Vec<Card> = Vec::new();
+            loop {
+                self.skip_whitespace();
+                if self.next_matches(',') {
+                    continue;
+                }
+                if self.peek().is_none() {
+                    break;
+                }
+                let start = self.cursor;
+                let card = Card::new(self.parse_card()?, self.parse_suit()?);
+                if cards.contains(&card) {
+                    return Err(self.error_from(start, ErrorKind::DuplicateCard(card.to_string())));
+                }
+                cards.push(card);
+            }
+            Ok(cards)
+        }
+
+        /// Parses an explicit two-card combo such as `AhKh` or `QsQd`.
+        pub fn parse_combo(&mut self) -> ParseResult<Combo> {
+            let start = self.cursor;
+            let first_card = Card::new(self.parse_card()?, self.parse_suit()?);
+            let second_card = Card::new(self.parse_card()?, self.parse_suit()?);
+            if first_card == second_card {
+                return Err(self.error_from(
+                    start,
+                    ErrorKind::InvalidCombo(format!("{}{}", first_card, second_card)),
+                ));
+            }
+            Ok(Combo::new(first_card, second_card))
+        }
+
+        /// Parses a range with an `x` wildcard kicker. `Ax` is every ace with a lower kicker (the
+        /// same as `A2+`), `Kxs` and `Qxo` limit that to suited or offsuit hands, and `xx` is
+        /// every hand. A `+` or `-` moves the first card instead, so `Kx+` is `Kx, Ax` and
+        /// `4xs-` is `4xs, 3xs`.
+        fn parse_wildcard_range(&mut self) -> ParseResult<Vec<Hand>> {
+            let start = self.cursor;
+            let first_card = if self.next_matches('x') || self.next_matches('X') {
+                None
+            } else {
+                Some(self.parse_card()?)
+            };
+            self.pop();
+            let suited_type = if self.next_matches('s') {
+                SuitedType::Suited
+            } else if self.next_matches('o') {
+                SuitedType::Offsuit
+            } else {
+                SuitedType::None
+            };
+            let (lowest, highest) = match (first_card, self.peek()) {
+                (None, Some('+' | '-')) => {
+                    self.pop();
+                    return Err(self.error_from(
+                        start,
+                        ErrorKind::InvalidRange(
+                            "Can't apply + or - when both cards are wildcards".to_string(),
+                        ),
+                    ));
+                }
+                (None, _) => (CardType::Two, CardType::Ace),
+                (Some(first_card), Some('+')) => {
+                    self.pop();
+                    (first_card, CardType::Ace)
+                }
+                (Some(first_card), Some('-')) => {
+                    self.pop();
+                    (CardType::Two, first_card)
+                }
+                (Some(first_card), _) => (first_card, first_card),
+            };
+            if let Some(other_character) = self.peek() {
+                if !Self::is_separator(other_character) {
+                    return Err(self.unexpected());
+                }
+            }
+            let mut range = Range::new();
+            if first_card.is_none() && suited_type == SuitedType::None {
+                range = pairs_between(CardType::Two, CardType::Ace);
+            }
+            for card in ranks_between(lowest, highest) {
+                range = range.union(&kickers_between(
+                    card,
+                    CardType::Two,
+                    CardType::Ace,
+                    &suited_type,
+                ));
+            }
+            Ok(range.hands())
+        }
+
+        /// Reads a `connectors`, `gappers`, `N-gappers` or `one-gappers` keyword followed by
+        /// whitespace, optionally after a `suited` or `offsuit` word, returning the gap it names
+        /// and the suitedness the family is limited to.
+        fn parse_family(&mut self) -> Option<(usize, SuitedType)> {
+            let start = self.cursor;
+            let suited_type = match self.next_word().as_str() {
+                "suited" => SuitedType::Suited,
+                "offsuit" | "offsuited" => SuitedType::Offsuit,
+                _ => SuitedType::None,
+            };
+            if suited_type != SuitedType::None {
+                self.skip_word();
+            }
+            let word = self.next_word();
+            let gap = match word.as_str() {
+                "connectors" => Some(0),
+                "gappers" => Some(1),
+                _ => word.strip_suffix("-gappers").and_then(|gap| match gap {
+                    "one" => Some(1),
+                    "two" => Some(2),
+                    "three" => Some(3),
+                    gap => gap.parse::<usize>().ok(),
+                }),
+            };
+            // The keyword must be followed by the hands it applies to.
+            match gap {
+                Some(gap) if self.skip_word() => Some((gap, suited_type)),
+                _ => {
+                    self.cursor = start;
+                    None
+                }
+            }
+        }
+
+        /// Returns the lowercased word at the cursor.
+        fn next_word(&self) -> String {
+            self.characters[self.cursor..]
+                .iter()
+                .take_while(|character| !character.is_whitespace())
+                .collect::<String>()
+                .to_lowercase()
+        }
+
+        /// Moves past the word at the cursor and the whitespace after it, returning whether
+        /// anything follows.
+        fn skip_word(&mut self) -> bool {
+            while self
+                .peek()
+                .is_some_and(|character| !character.is_whitespace())
+            {
+                self.pop();
+            }
+            self.skip_whitespace();
+            self.peek().is_some()
+        }
+
+        /// Parses the hands after a `connectors` or `N-gappers` keyword, where both ranks move
+        /// together: `54s+` runs up to the highest hand in the family, `T8s-` down to the
+        /// lowest, `T8s-53s` between the two, and a lone hand is just that hand. A `suited` or
+        /// `offsuit` word before the keyword marks unmarked hands, and must agree with any
+        /// `s` or `o` they have.
+        fn parse_connector_range(
+            &mut self,
+            gap: usize,
+            family_suited_type: SuitedType,
+        ) -> ParseResult<Vec<Hand>> {
+            let family_hand = |parser: &mut Self| {
+                let start = parser.cursor;
+                let hand = parser.parse_hand()?;
+                if hand.gap() != Some(gap) {
+                    let family = match gap {
+                        0 => "a connector".to_string(),
+                        gap => format!("a {}-gapper", gap),
+                    };
+                    return Err(parser.error_from(
+                        start,
+                        ErrorKind::InvalidRange(format!("{} isn't {}", hand, family)),
+                    ));
+                }
+                match (&family_suited_type, &hand.2) {
+                    (SuitedType::None, _) => Ok(hand),
+                    (family, SuitedType::None) => Ok(Hand::from_hand(&hand, family.clone())),
+                    (family, suited_type) if family == suited_type => Ok(hand),
+                    (family, _) => {
+                        let family = match family {
+                            SuitedType::Suited => "suited",
+                            _ => "offsuit",
+                        };
+                        Err(parser.error_from(
+                            start,
+                            ErrorKind::InvalidRange(format!(
+                                "{} isn't {} like the family it's in",
+                                hand, family
+                            )),
+                        ))
+                    }
+                }
+            };
+            let hand = family_hand(self)?;
+            let connectors = Connectors::new(gap).with_suited_type(hand.2.clone());
+            let start = self.cursor;
+            let (low, high) = match self.peek() {
+                Some('+') => {
+                    self.pop();
+                    (hand.0, CardType::Ace)
+                }
+                Some('-') => {
+                    self.pop();
+                    if self.at_range_end() {
+                        (CardType::Two, hand.0)
+                    } else {
+                        let second_hand = family_hand(self)?;
+                        if second_hand.2 != hand.2 {
+                            return Err(self.error_from(
+                                start,
+                                ErrorKind::InvalidRange(format!(
+                                    "{}-{} mixes suited, offsuit and unmarked hands",
+                                    hand, second_hand
+                                )),
+                            ));
+                        }
+                        (hand.0, second_hand.0)
+                    }
+                }
+                Some(other_character) if !Self::is_separator(other_character) => {
+                    return Err(self.unexpected());
+                }
+                _ => (hand.0, hand.0),
+            };
+            Ok(connectors.with_first_cards(low, high).hands())
+        }
+
+        /// Parses a `15%` or `top 15%` range into the strongest hands in the parser's ranking
+        /// that make up that share of all combos.
+        fn parse_percentile_range(&mut self) -> ParseResult<Vec<Hand>> {
+            let start = self.cursor;
+            self.cursor += self.top_keyword_length();
+            let number_start = self.cursor;
+            let percentage = self.parse_number()?;
+            self.expect('%')?;
+            if percentage > 100.0 {
+                return Err(self.error_from(
+                    number_start,
+                    ErrorKind::InvalidRange(format!("{}% is more than every hand", percentage)),
+                ));
+            }
+            let hands = self.ranking.top(percentage);
+            if hands.is_empty() {
+                return Err(self.error_from(
+                    start,
+                    ErrorKind::InvalidRange(format!(
+                        "top {}% is too small to hold a hand",
+                        percentage
+                    )),
+                ));
+            }
+            Ok(hands)
+        }
+
+        /// Returns the length of a `top` keyword and the whitespace after it at the cursor, or
+        /// 0 if there isn't one.
+        fn top_keyword_length(&self) -> usize {
+            let rest = &self.characters[self.cursor..];
+            let word: String = rest.iter().take(3).collect();
+            let spaces = rest
+                .iter()
+                .skip(3)
+                .take_while(|character| character.is_whitespace())
+                .count();
+            if word.eq_ignore_ascii_case("top") && spaces > 0 {
+                3 + spaces
+            } else {
+                0
+            }
+        }
+
+        /// A percentile range is a `top` keyword, or a number followed by `%`.
+        fn next_is_percentile(&self) -> bool {
+            if self.top_keyword_length() > 0 {
+                return true;
+            }
+            let digits = self.characters[self.cursor..]
+                .iter()
+                .take_while(|character| character.is_ascii_digit() || **character == '.')
+                .count();
+            digits > 0 && self.characters.get(self.cursor + digits) == Some(&'%')
+        }
+
+        fn next_is_wildcard(&self) -> bool {
+            matches!(self.characters.get(self.cursor + 1), Some('x') | Some('X'))
+        }
+
+        /// A combo is a rank followed by a suit followed by another rank, which tells `AsKs`
+        /// apart from the suited class `AKs`.
+        fn next_is_combo(&self) -> bool {
+            let character_at = |offset: usize| self.characters.get(self.cursor + offset).copied();
+            matches!(character_at(0), Some(character) if CardType::from_char(character).is_some())
+                && matches!(character_at(1), Some(character) if Suit::from_char(character).is_some())
+                && matches!(character_at(2), Some(character) if CardType::from_char(character).is_some())
+        }
+
+        /// Returns `hand` and every hand above it: higher pairs for a pair, or higher kickers
+        /// below the same first card otherwise.
+        fn get_gte_hands(&self, hand: &Hand) -> Range {
+            if hand.is_pair() {
+                return pairs_between(hand.0, CardType::Ace);
+            }
+            kickers_between(hand.0, hand.1, hand.0, &hand.2)
+        }
+
+        /// Returns `hand` and every hand below it: lower pairs for a pair, or lower kickers
+        /// with the same first card otherwise.
+        fn get_lte_hands(&self, hand: &Hand) -> Range {
+            if hand.is_pair() {
+                return pairs_between(CardType::Two, hand.0);
+            }
+            kickers_between(hand.0, CardType::Two, hand.1, &hand.2)
+        }
+
+        /// Returns the hands in a span, in either order: pairs between two pairs (`22-55`), the
+        /// kickers between two hands with the same first card and suitedness (`A2s-A5s`,
+        /// `KQo-K9o`, or `A2-A5` for both), or the connectors or gappers between two hands
+        /// with the same gap (`T8s-53s`). Any other pair of ends is an error.
+        fn get_hands_between(
+            &self,
+            first_hand: &Hand,
+            second_hand: &Hand,
+        ) -> Result<Range, ErrorKind> {
+            if first_hand.is_pair() && second_hand.is_pair() {
+                Ok(pairs_between(first_hand.0, second_hand.0))
+            } else if first_hand.is_pair() || second_hand.is_pair() {
+                Err(ErrorKind::InvalidRange(format!(
+                    "{}-{} mixes a pair with a non-pair hand",
+                    first_hand, second_hand
+                )))
+            } else if first_hand.2 != second_hand.2 {
+                Err(ErrorKind::InvalidRange(format!(
+                    "{}-{} mixes suited, offsuit and unmarked hands",
+                    first_hand, second_hand
+                )))
+            } else if first_hand.0 != second_hand.0 {
+                match first_hand.gap() {
+                    Some(gap) if second_hand.gap() == Some(gap) => Ok(Connectors::new(gap)
+                        .with_suited_type(first_hand.2.clone())
+                        .with_first_cards(first_hand.0, second_hand.0)
+                        .range()),
+                    _ => Err(ErrorKind::InvalidRange(format!(
+                        "{}-{} must keep either the same first card or the same gap",
+                        first_hand, second_hand
+                    ))),
+                }
+            } else {
+                Ok(kickers_between(
+                    first_hand.0,
+                    first_hand.1,
+                    second_hand.1,
+                    &first_hand.2,
+                ))
+            }
+        }
+    }
+
+    /// Returns the pairs from `low` up to `high`, in either order.
+    fn pairs_between(low: CardType, high: CardType) -> Range {
+        ranks_between(low, high)
+            .map(|card| Hand(card, card, SuitedType::None))
+            .collect()
+    }
+
+    /// Returns the hands with `first_card` and a kicker from `low` up to `high`, in either
+    /// order and below `first_card`, as both suited and offsuit hands for `SuitedType::None`.
+    fn kickers_between(
+        first_card: CardType,
+        low: CardType,
+        high: CardType,
+        suited_type: &SuitedType,
+    ) -> Range {
+        let suited_types: &[SuitedType] = match suited_type {
+            SuitedType::None => &[SuitedType::Suited, SuitedType::Offsuit],
+            SuitedType::Suited => &[SuitedType::Suited],
+            SuitedType::Offsuit => &[SuitedType::Offsuit],
+        };
+        ranks_between(low, high)
+            .filter(|kicker| *kicker < first_card)
+            .flat_map(|kicker| {
+                suited_types
+                    .iter()
+                    .map(move |suited_type| Hand(first_card, kicker, suited_type.clone()))
+            })
+            .collect()
+    }
+
+    /// Iterates over the ranks from `low` up to `high`, in either order.
+    fn ranks_between(low: CardType, high: CardType) -> impl Iterator<Item = CardType> {
+        let (low, high) = (low.min(high), low.max(high));
+        CARD_TYPES
+            .iter()
+            .copied()
+            .filter(move |card| (low..=high).contains(card))
+    }
 
     #[cfg(test)]
     mod tests {
@@ -546,10 +1798,9 @@ pub mod parser {
 
         #[test]
         fn test_partial_ord() {
-            assert_eq!(
+            assert!(
                 Hand(CardType::Ace, CardType::Two, SuitedType::Suited)
-                    < Hand(CardType::Ace, CardType::Three, SuitedType::Suited),
-                true
+                    < Hand(CardType::Ace, CardType::Three, SuitedType::Suited)
             );
         }
 
@@ -635,11 +1886,572 @@ pub mod parser {
             );
         }
 
+        #[test]
+        fn parses_range_list() {
+            let mut parser = Parser::new("QQ+,  AKs ,AJs-AQs");
+            let result = parser.parse_range_list();
+            assert_eq!(
+                Ok(vec![
+                    Hand(CardType::Ace, CardType::Ace, SuitedType::None),
+                    Hand(CardType::King, CardType::King, SuitedType::None),
+                    Hand(CardType::Queen, CardType::Queen, SuitedType::None),
+                    Hand(CardType::Ace, CardType::King, SuitedType::Suited),
+                    Hand(CardType::Ace, CardType::Queen, SuitedType::Suited),
+                    Hand(CardType::Ace, CardType::Jack, SuitedType::Suited),
+                ]),
+                result
+            );
+        }
+
+        #[test]
+        fn dedups_range_list() {
+            let mut parser = Parser::new("KK+, AA, AK, AKs");
+            let result = parser.parse_range_list();
+            assert_eq!(
+                Ok(vec![
+                    Hand(CardType::Ace, CardType::Ace, SuitedType::None),
+                    Hand(CardType::King, CardType::King, SuitedType::None),
+                    Hand(CardType::Ace, CardType::King, SuitedType::Suited),
+                    Hand(CardType::Ace, CardType::King, SuitedType::Offsuit),
+                ]),
+                result
+            );
+        }
+
+        #[test]
+        fn errs_on_trailing_separator() {
+            let mut parser = Parser::new("AA, KK,");
+            let result = parser.parse_range_list();
+            assert_eq!(ErrorKind::EndOfLine, *result.unwrap_err().kind());
+        }
+
+        #[test]
+        fn parses_colon_weights() {
+            let mut parser = Parser::new("AA, AKs:0.5, JJ-QQ:0.25");
+            let range = parser.parse_weighted_range_list().unwrap();
+            assert_eq!(
+                range.frequency(&Hand(CardType::Ace, CardType::Ace, SuitedType::None)),
+                1.0
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Ace, CardType::King, SuitedType::Suited)),
+                0.5
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Jack, CardType::Jack, SuitedType::None)),
+                0.25
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Ace, CardType::King, SuitedType::Offsuit)),
+                0.0
+            );
+            assert_eq!(range.len(), 4);
+        }
+
+        #[test]
+        fn parses_bracket_weights() {
+            let mut parser = Parser::new("KK+, [50]QQ, AKo:0.1[/50], [25]AKs[/25]");
+            let range = parser.parse_weighted_range_list().unwrap();
+            assert_eq!(
+                range.frequency(&Hand(CardType::King, CardType::King, SuitedType::None)),
+                1.0
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Queen, CardType::Queen, SuitedType::None)),
+                0.5
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Ace, CardType::King, SuitedType::Offsuit)),
+                0.1
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Ace, CardType::King, SuitedType::Suited)),
+                0.25
+            );
+        }
+
+        #[test]
+        fn errs_on_invalid_weights() {
+            let mut parser = Parser::new("AKs:1.5");
+            assert_eq!(
+                ErrorKind::InvalidFrequency("1.5".to_string()),
+                *parser.parse_weighted_range_list().unwrap_err().kind()
+            );
+            let mut parser = Parser::new("[50]AKs[/25]");
+            assert!(matches!(
+                parser.parse_weighted_range_list().unwrap_err().kind(),
+                ErrorKind::InvalidFrequency(_)
+            ));
+            let mut parser = Parser::new("AKs:0.5");
+            assert_eq!(
+                ErrorKind::InvalidToken(":".to_string()),
+                *parser.parse_range_list().unwrap_err().kind()
+            );
+        }
+
+        #[test]
+        fn parses_combos_mixed_with_classes() {
+            let mut parser = Parser::new("QQ+, AhKh, KsQs, AKo");
+            let result = parser.parse_element_list().unwrap();
+            let ace_hearts = Card::new(CardType::Ace, Suit::Hearts);
+            let king_hearts = Card::new(CardType::King, Suit::Hearts);
+            assert_eq!(result.len(), 6);
+            assert_eq!(
+                result[3],
+                RangeElement::Combo(Combo::new(ace_hearts, king_hearts))
+            );
+            assert_eq!(result[4].to_string(), "KsQs");
+            assert_eq!(
+                result[5],
+                RangeElement::Class(Hand(CardType::Ace, CardType::King, SuitedType::Offsuit))
+            );
+        }
+
+        #[test]
+        fn errs_on_invalid_combos() {
+            let mut parser = Parser::new("AhAh");
+            assert_eq!(
+                ErrorKind::InvalidCombo("AhAh".to_string()),
+                *parser.parse_element_list().unwrap_err().kind()
+            );
+            let mut parser = Parser::new("AhK");
+            assert_eq!(
+                ErrorKind::EndOfLine,
+                *parser.parse_element_list().unwrap_err().kind()
+            );
+            let mut parser = Parser::new("AhKh+");
+            assert_eq!(
+                ErrorKind::InvalidToken("+".to_string()),
+                *parser.parse_element_list().unwrap_err().kind()
+            );
+        }
+
+        #[test]
+        fn expands_hands_into_combos() {
+            let pair = Hand(CardType::Queen, CardType::Queen, SuitedType::None);
+            let suited = Hand(CardType::Ace, CardType::King, SuitedType::Suited);
+            let offsuit = Hand(CardType::Ace, CardType::King, SuitedType::Offsuit);
+            assert_eq!(pair.combos().len(), 6);
+            assert_eq!(suited.combos().len(), 4);
+            assert_eq!(offsuit.combos().len(), 12);
+            assert!(pair.combos().iter().all(|combo| combo.hand() == pair));
+            assert!(offsuit.combos().iter().all(|combo| combo.hand() == offsuit));
+            let total: usize = VALID_HANDS.iter().map(|hand| hand.combos().len()).sum();
+            assert_eq!(total, cards::TOTAL_COMBOS);
+        }
+
+        #[test]
+        fn counts_range_combos() {
+            let mut parser = Parser::new("QQ+, AKs, AhKh, AsQd");
+            let combos = parser.parse_combo_list().unwrap();
+            assert_eq!(combos.len(), 23);
+            assert_eq!(
+                format!("{:.2}", cards::combo_percentage(combos.len())),
+                "1.73"
+            );
+
+            let mut parser = Parser::new("AA, AKs:0.5");
+            let range = parser.parse_weighted_range_list().unwrap();
+            assert_eq!(range.combo_count(), 8.0);
+
+            // An empty sum of floats is -0.0, which would print as `-0 combos`.
+            let range = Parser::new("AA, !AA").parse_weighted_range_list().unwrap();
+            assert!(range.combo_count().is_sign_positive());
+            assert_eq!(format!("{:.1}", range.percentage()), "0.0");
+        }
+
+        #[test]
+        fn parses_wildcard_ranges() {
+            let parse = |range: &str| Parser::new(range).parse_range_list().unwrap();
+            assert_eq!(parse("Ax"), parse("A2+"));
+            assert_eq!(parse("Kxs"), parse("K2s+"));
+            assert_eq!(parse("Qxo+"), parse("A2o+, K2o+, Q2o+"));
+            assert_eq!(parse("4x-"), parse("42+, 32"));
+            assert_eq!(parse("xx").len(), 169);
+            assert_eq!(parse("xxs").len(), 78);
+            assert_eq!(parse("xxo").len(), 78);
+            assert_eq!(
+                *Parser::new("xx+").parse_range_list().unwrap_err().kind(),
+                ErrorKind::InvalidRange(
+                    "Can't apply + or - when both cards are wildcards".to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn parses_exclusions() {
+            let parse = |range: &str| Parser::new(range).parse_range_list().unwrap();
+            assert_eq!(parse("22+ !AA"), parse("22-KK"));
+            assert_eq!(parse("ATs+, KQs, !AKs"), parse("ATs-AQs, KQs"));
+            assert_eq!(parse("A2s+ - A5s"), parse("A6s+, A2s-A4s"));
+            assert_eq!(parse("AKs - QJs"), parse("AKs"));
+            // Exclusions only remove what's listed before them.
+            assert_eq!(parse("!AKs, AKs"), parse("AKs"));
+            assert_eq!(parse("!AKs, AKs+"), parse("AKs+"));
+            assert_eq!(parse("AKs+, !AKs, AKs"), parse("AKs+"));
+            assert_eq!(
+                parse("(A2s+, K9s+) - (A5s, K9s)"),
+                parse("A6s+, A2s-A4s, KTs+")
+            );
+            assert_eq!(parse("xx !(22+, Ax)").len(), 169 - 13 - 24);
+        }
+
+        #[test]
+        fn parses_spans_with_spaced_dashes() {
+            let parse = |range: &str| Range::parse(range).unwrap();
+            assert_eq!(parse("QQ - 99"), parse("QQ-99"));
+            assert_eq!(parse("QQ - 99").combo_count(), 24);
+            assert_eq!(parse("22 - 55, AKs"), parse("22-55, AKs"));
+            assert_eq!(parse("A5s - A2s"), parse("A2s-A5s"));
+            let range = Parser::new("QQ - 99:0.5")
+                .parse_weighted_range_list()
+                .unwrap();
+            assert_eq!(range.combo_count(), 12.0);
+            assert_eq!(parse("QQ+ - KK"), parse("QQ, AA"));
+        }
+
+        #[test]
+        fn excludes_combos_from_classes() {
+            let mut parser = Parser::new("AKs, QQ, !AhKh");
+            let result = parser.parse_element_list().unwrap();
+            assert_eq!(
+                result.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+                vec!["AsKs", "AdKd", "AcKc", "QQ"]
+            );
+
+            // A combo that's also listed on its own is only kept once.
+            let result = Parser::new("AKs, AhKh, !AsKs")
+                .parse_element_list()
+                .unwrap();
+            assert_eq!(
+                result.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+                vec!["AdKd", "AcKc", "AhKh"]
+            );
+            let result = Parser::new("AhKh:0.25, AKs:0.5, !AsKs")
+                .parse_weighted_element_list()
+                .unwrap();
+            let elements: Vec<String> = result.iter().map(|(e, _)| e.to_string()).collect();
+            assert_eq!(elements, vec!["AhKh", "AdKd", "AcKc"]);
+            assert!(result.iter().all(|(_, frequency)| *frequency == 0.5));
+        }
+
+        #[test]
+        fn parses_weighted_groups() {
+            let mut parser = Parser::new("(AKs, AQs):0.5, [50]AA (KK, QQ):0.2[/50]");
+            let range = parser.parse_weighted_range_list().unwrap();
+            assert_eq!(
+                range.frequency(&Hand(CardType::Ace, CardType::Queen, SuitedType::Suited)),
+                0.5
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Ace, CardType::Ace, SuitedType::None)),
+                0.5
+            );
+            assert_eq!(
+                range.frequency(&Hand(CardType::Queen, CardType::Queen, SuitedType::None)),
+                0.2
+            );
+        }
+
+        #[test]
+        fn errs_on_unclosed_group() {
+            let mut parser = Parser::new("(AKs, AQs");
+            assert_eq!(
+                ErrorKind::EndOfLine,
+                *parser.parse_range_list().unwrap_err().kind()
+            );
+            let mut parser = Parser::new("AKs)");
+            assert_eq!(
+                ErrorKind::InvalidToken(")".to_string()),
+                *parser.parse_range_list().unwrap_err().kind()
+            );
+        }
+
+        #[test]
+        fn indexes_valid_hands() {
+            for (index, hand) in VALID_HANDS.iter().enumerate() {
+                assert_eq!(hand.index(), Some(index));
+            }
+            assert_eq!(
+                Hand(CardType::King, CardType::Ace, SuitedType::Suited).index(),
+                None
+            );
+            assert_eq!(
+                Hand(CardType::Ace, CardType::Ace, SuitedType::Suited).index(),
+                None
+            );
+        }
+
+        #[test]
+        fn parses_into_sets() {
+            let range = Parser::new("QQ+, AKs, !KK").parse_range_set().unwrap();
+            assert_eq!(range.len(), 3);
+            assert_eq!(range.combo_count(), 16);
+            let combos = Parser::new("QQ+, AKs, AhKh, AhQd")
+                .parse_combo_set()
+                .unwrap();
+            assert_eq!(combos.len(), 23);
+            assert!(matches!(
+                Parser::new("AKs, AhQd")
+                    .parse_range_set()
+                    .unwrap_err()
+                    .kind(),
+                ErrorKind::InvalidRange(_)
+            ));
+        }
+
+        #[test]
+        fn locates_errors() {
+            let error = Parser::new("QQ+, AKz").parse_range_list().unwrap_err();
+            assert_eq!(error.span(), 7..8);
+            assert_eq!(error.source_text(), "QQ+, AKz");
+            assert_eq!(
+                error.render(),
+                "error: Unexpected token: z\n --> 1:8\n  |\n1 | QQ+, AKz\n  |        ^"
+            );
+
+            let error = Parser::new("  AA, AKs:1.5")
+                .parse_weighted_range_list()
+                .unwrap_err();
+            assert_eq!(error.span(), 10..13);
+            let error = Parser::new("AA, KK,").parse_range_list().unwrap_err();
+            assert_eq!(error.span(), 7..7);
+            assert!(error.render().ends_with("\n  |        ^"));
+            let error = Parser::new("QQ+,\nAKs:0.5").parse_range_list().unwrap_err();
+            assert_eq!(error.line_column(), (2, 4));
+            assert!(error.render().contains("\n2 | AKs:0.5\n  |    ^"));
+            let error = Parser::new("AKs, AhQd").parse_range_set().unwrap_err();
+            assert_eq!(error.span(), 5..9);
+        }
+
+        #[test]
+        fn recovers_from_errors() {
+            let (hands, errors) = Parser::new("QQ+, AKz, KQs JTx9, !QQ, 76s").recover_range_list();
+            assert_eq!(
+                hands,
+                Parser::new("AA, KK, KQs, 76s").parse_range_list().unwrap()
+            );
+            assert_eq!(errors.len(), 2);
+            assert_eq!(errors[0].span(), 7..8);
+            assert_eq!(*errors[1].kind(), ErrorKind::InvalidToken("x".to_string()));
+
+            let (range, errors) =
+                Parser::new("AA:2, (KK, QQ:0.5, AKs, , AhKh), (JJ").recover_weighted_range_list();
+            assert_eq!(range.len(), 3);
+            assert_eq!(
+                range.frequency(&Hand::new(
+                    CardType::Queen,
+                    CardType::Queen,
+                    SuitedType::None
+                )),
+                0.5
+            );
+            let kinds: Vec<ErrorKind> = errors.iter().map(|error| error.kind().clone()).collect();
+            assert_eq!(
+                kinds,
+                vec![
+                    ErrorKind::InvalidFrequency("2".to_string()),
+                    ErrorKind::InvalidToken(",".to_string()),
+                    ErrorKind::InvalidRange(
+                        "AhKh is a specific combo, not a hand class".to_string()
+                    ),
+                    ErrorKind::EndOfLine,
+                ]
+            );
+
+            let (hands, errors) = Parser::new("AA, KK").recover_range_list();
+            assert_eq!(hands.len(), 2);
+            assert!(errors.is_empty());
+        }
+
+        #[test]
+        fn validates_hands() {
+            let kind = |range: &str| {
+                Parser::new(range)
+                    .parse_range_list()
+                    .unwrap_err()
+                    .kind()
+                    .clone()
+            };
+            assert_eq!(kind("AAs"), ErrorKind::SuitedPair("AAs".to_string()));
+            assert_eq!(kind("22, TTo+"), ErrorKind::SuitedPair("TTo".to_string()));
+            assert_eq!(kind("KA"), ErrorKind::ReversedRanks("KA".to_string()));
+            assert_eq!(
+                kind("A2s-A5s, 5As"),
+                ErrorKind::ReversedRanks("5As".to_string())
+            );
+
+            let lenient = |range: &str| {
+                Parser::new(range)
+                    .with_validation(Validation::Lenient)
+                    .parse_range_list()
+            };
+            assert_eq!(
+                lenient("KA, 2Ao, 3As-5As").unwrap(),
+                Parser::new("AK, A2o, A3s-A5s").parse_range_list().unwrap()
+            );
+            assert_eq!(
+                *lenient("QQs").unwrap_err().kind(),
+                ErrorKind::SuitedPair("QQs".to_string())
+            );
+        }
+
+        #[test]
+        fn parses_forgiving_spelling() {
+            let parse = |range: &str| {
+                Parser::new(range)
+                    .with_spelling(Spelling::Forgiving)
+                    .parse_range_list()
+            };
+            assert_eq!(
+                parse("qq+, aks, ak s, A10o, king queen suited").unwrap(),
+                Parser::new("QQ+, AKs, ATo, KQs")
+                    .parse_range_list()
+                    .unwrap()
+            );
+            assert_eq!(
+                Parser::new("A♠K♠, 10♥9♥")
+                    .with_spelling(Spelling::Forgiving)
+                    .parse_combo_list()
+                    .unwrap(),
+                Parser::new("AsKs, Th9h").parse_combo_list().unwrap()
+            );
+            assert!(Parser::new("aks").parse_range_list().is_err());
+
+            let error = parse("ace king suited, queen jack z").unwrap_err();
+            assert_eq!(error.span(), 28..29);
+        }
+
+        #[test]
+        fn parses_spans_in_either_order() {
+            let parse = |range: &str| Parser::new(range).parse_range_list().unwrap();
+            assert_eq!(parse("55-22"), parse("22-55"));
+            assert_eq!(parse("55-22").len(), 4);
+            assert_eq!(parse("A5s-A2s"), parse("A2s-A5s"));
+            assert_eq!(parse("KQs-K9s"), parse("K9s+"));
+            assert_eq!(parse("KJo-KTo"), parse("KJo, KTo"));
+            assert_eq!(
+                Range::parse("A5-A2").unwrap(),
+                Range::parse("A2s-A5s, A2o-A5o").unwrap()
+            );
+            assert_eq!(parse("TT-TT"), parse("TT"));
+
+            let kind = |range: &str| {
+                Parser::new(range)
+                    .parse_range_list()
+                    .unwrap_err()
+                    .kind()
+                    .clone()
+            };
+            assert!(matches!(kind("KQs-QTs"), ErrorKind::InvalidRange(_)));
+            assert!(matches!(kind("AKs-AQo"), ErrorKind::InvalidRange(_)));
+            assert!(matches!(kind("AKs-AQ"), ErrorKind::InvalidRange(_)));
+            assert!(matches!(kind("22-A2s"), ErrorKind::InvalidRange(_)));
+            let error = Parser::new("AA, KQs-QTs").parse_range_list().unwrap_err();
+            assert_eq!(error.span(), 4..11);
+        }
+
+        #[test]
+        fn parses_connectors_and_gappers() {
+            let parse = |range: &str| Range::parse(range).unwrap();
+            assert_eq!(
+                parse("connectors 54s+"),
+                parse("54s, 65s, 76s, 87s, 98s, T9s, JTs, QJs, KQs, AKs")
+            );
+            assert_eq!(parse("T8s-53s"), parse("T8s, 97s, 86s, 75s, 64s, 53s"));
+            assert_eq!(parse("1-gappers T8s-53s"), parse("T8s-53s"));
+            assert_eq!(parse("Gappers 53s-"), parse("53s, 42s"));
+            assert_eq!(parse("2-gappers KTo"), parse("KTo"));
+            assert_eq!(parse("connectors 76").len(), 2);
+            assert_eq!(parse("connectors 54s+, !AKs").len(), 9);
+            assert_eq!(parse("suited connectors 54s+"), parse("connectors 54s+"));
+            assert_eq!(parse("suited connectors 54+"), parse("connectors 54s+"));
+            assert_eq!(parse("Offsuit connectors 76-54"), parse("76o, 65o, 54o"));
+            assert_eq!(parse("one-gappers T8s-53s"), parse("T8s-53s"));
+            assert_eq!(parse("two-gappers KTo"), parse("KTo"));
+            let forgiving = |range: &str| {
+                Parser::new(range)
+                    .with_spelling(Spelling::Forgiving)
+                    .parse_range_list()
+                    .unwrap()
+            };
+            assert_eq!(
+                forgiving("suited connectors 54s+"),
+                forgiving("connectors 54s+")
+            );
+            assert_eq!(forgiving("one-gappers t8s-53s"), forgiving("T8s-53s"));
+
+            let kind = |range: &str| {
+                Parser::new(range)
+                    .parse_range_list()
+                    .unwrap_err()
+                    .kind()
+                    .clone()
+            };
+            assert!(matches!(
+                kind("connectors 64s+"),
+                ErrorKind::InvalidRange(_)
+            ));
+            assert!(matches!(
+                kind("1-gappers T8s-54s"),
+                ErrorKind::InvalidRange(_)
+            ));
+            assert!(matches!(kind("T8s-54s"), ErrorKind::InvalidRange(_)));
+            assert!(matches!(kind("connectors"), ErrorKind::InvalidToken(_)));
+            assert!(matches!(
+                kind("suited connectors 54o+"),
+                ErrorKind::InvalidRange(_)
+            ));
+            assert!(matches!(
+                kind("offsuit connectors 76-54s"),
+                ErrorKind::InvalidRange(_)
+            ));
+        }
+
+        #[test]
+        fn parses_percentile_ranges() {
+            let parse = |range: &str| Range::parse(range).unwrap();
+            assert_eq!(parse("2%"), parse("JJ+"));
+            assert_eq!(parse("top 2%, AKo"), parse("JJ+, AKo"));
+            assert_eq!(parse("TOP 100%"), Range::full());
+            assert_eq!(parse("top 20%, !5%"), parse("20%").difference(&parse("5%")));
+
+            let range = Parser::new("top 2%")
+                .with_ranking(Ranking::SklanskyChubukov)
+                .parse_range_set()
+                .unwrap();
+            assert_eq!(range, parse("JJ+, AKs"));
+            let range = Parser::new("50%")
+                .with_ranking(Ranking::parse("AKs, QQ").unwrap())
+                .parse_range_set()
+                .unwrap();
+            assert_eq!(range, parse("AKs, QQ"));
+            let range = Parser::new("10%:0.5")
+                .with_spelling(Spelling::Forgiving)
+                .parse_weighted_range_list()
+                .unwrap();
+            assert_eq!(range.len(), parse("10%").len());
+
+            let kind = |range: &str| {
+                Parser::new(range)
+                    .parse_range_list()
+                    .unwrap_err()
+                    .kind()
+                    .clone()
+            };
+            assert!(matches!(kind("150%"), ErrorKind::InvalidRange(_)));
+            assert!(matches!(kind("top 0.1%"), ErrorKind::InvalidRange(_)));
+            assert_eq!(kind("top 15"), ErrorKind::EndOfLine);
+        }
+
         #[test]
         fn errs_on_invalid_token() {
             let mut parser = Parser::new("ATk");
             let result = parser.parse_range();
-            assert_eq!(Err(ParseError::InvalidToken("k".to_string())), result);
+            assert_eq!(
+                ErrorKind::InvalidToken("k".to_string()),
+                *result.unwrap_err().kind()
+            );
         }
     }
 }
@@ -1,33 +1,284 @@
-use pfrange::parser::{Hand, Parser};
+use clap::Parser as ClapParser;
+use pfrange::chart::Chart;
+use pfrange::cli::{Args, Command, Format, Mode, ParseReport};
+use pfrange::equity::{EquityCalculator, Method};
+use pfrange::formats::{self, Dialect};
+use pfrange::lexer::Spelling;
+use pfrange::parser::{ParseError, ParseResult, Parser, WeightedRange, VALID_HANDS};
+use pfrange::utils::Rng;
+use pfrange::{grid, load_range_file};
+use std::error::Error;
+use std::fs;
 use std::io;
-use std::io::Write;
+use std::io::{IsTerminal, Read, Write};
+use std::process;
 
 fn main() {
-    println!("PFRange v0.0.1");
-    println!("Press Ctrl+c to exit\n");
+    let args = Args::parse();
+    let spelling = args.spelling();
+    let result = match args.command {
+        None => run_repl(args.mode, spelling),
+        Some(Command::Parse { file, ranges }) => read_input(file.as_deref(), &ranges)
+            .and_then(|input| run_parse(&input, args.mode, spelling)),
+        Some(Command::Grid { ranges }) => run_grid(&ranges.join(" "), args.mode, spelling),
+        Some(Command::Count { ranges }) => run_count(&ranges.join(" "), args.mode, spelling),
+        Some(Command::Convert { from, to, ranges }) => {
+            run_convert(&ranges.join(" "), from, to, args.mode, spelling)
+        }
+        Some(Command::Equity {
+            board,
+            dead,
+            exact,
+            samples,
+            seed,
+            ranges,
+        }) => {
+            let method = if exact {
+                Method::Exact
+            } else {
+                Method::MonteCarlo {
+                    samples,
+                    seed: seed.unwrap_or_else(|| Rng::from_time().next_u64()),
+                }
+            };
+            let cards = (board.as_deref(), dead.as_deref());
+            run_equity(&ranges, cards, method, args.mode, spelling)
+        }
+        Some(Command::Train { file }) => run_train(&file, args.mode),
+    };
+    if let Err(e) = result {
+        // A reader that stops early, like `pfrange parse ... | head`, isn't an error.
+        if let Some(io_error) = e.downcast_ref::<io::Error>() {
+            if io_error.kind() == io::ErrorKind::BrokenPipe {
+                return;
+            }
+        }
+        match e.downcast_ref::<ParseError>() {
+            Some(parse_error) => eprintln!("{}", parse_error.render()),
+            None => eprintln!("error: {}", e),
+        }
+        process::exit(1);
+    }
+}
+
+fn run_repl(mode: Mode, spelling: Spelling) -> Result<(), Box<dyn Error>> {
+    let mut out = io::stdout().lock();
+    writeln!(out, "PFRange v0.0.1 ({} mode)", mode)?;
+    writeln!(out, "Press Ctrl+c to exit\n")?;
 
+    let color = out.is_terminal();
     loop {
-        print!("Enter ranges: ");
-        io::stdout().flush().unwrap();
+        write!(out, "Enter ranges: ")?;
+        out.flush()?;
         let mut ranges = String::new();
 
-        io::stdin()
-            .read_line(&mut ranges)
-            .expect("Error getting range");
-        let mut range_vector = ranges.split(",").collect::<Vec<&str>>();
-        let mut results: Vec<Vec<Hand>> = Vec::new();
-        for i in 0..range_vector.len() {
-            range_vector[i] = range_vector[i].trim();
-            let mut parser: Parser = Parser::new(range_vector[i]);
-            match parser.parse_range() {
-                Ok(hands) => {
-                    results.push(hands);
+        if io::stdin().read_line(&mut ranges)? == 0 {
+            return Ok(());
+        }
+        // Show every mistake at once, along with whatever could still be parsed.
+        let (range, errors) = mode.recover_range(&ranges, spelling);
+        for error in errors.iter() {
+            eprintln!("{}\n", error.render());
+        }
+        if errors.is_empty() || !range.is_empty() {
+            writeln!(out, "{}", grid::render_weighted_range(&range, color))?;
+            print_count(&mut out, &range)?;
+        }
+    }
+}
+
+/// Reads the range text from `file` (`-` for stdin), the arguments, or stdin when neither is
+/// given, so that `parse` works at the end of a pipeline.
+fn read_input(file: Option<&str>, ranges: &[String]) -> Result<String, Box<dyn Error>> {
+    let mut input = String::new();
+    match file {
+        Some("-") => {
+            io::stdin().read_to_string(&mut input)?;
+        }
+        Some(path) => input = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?,
+        None if ranges.is_empty() => {
+            io::stdin().read_to_string(&mut input)?;
+        }
+        None => input = ranges.join(" "),
+    }
+    Ok(input)
+}
+
+/// Parses a range, printing every error in it before giving up.
+fn parse_range(
+    ranges: &str,
+    mode: Mode,
+    spelling: Spelling,
+) -> Result<WeightedRange, Box<dyn Error>> {
+    let (range, errors) = mode.recover_range(ranges, spelling);
+    match errors.len() {
+        0 => Ok(range),
+        1 => Err(Box::new(errors[0].clone())),
+        count => {
+            for error in errors.iter() {
+                eprintln!("{}\n", error.render());
+            }
+            Err(format!("aborting due to {} previous errors", count).into())
+        }
+    }
+}
+
+fn run_parse(ranges: &str, mode: Mode, spelling: Spelling) -> Result<(), Box<dyn Error>> {
+    let report = ParseReport::new(&parse_range(ranges, mode, spelling)?);
+    let mut out = io::stdout().lock();
+    writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+    Ok(())
+}
+
+fn run_grid(ranges: &str, mode: Mode, spelling: Spelling) -> Result<(), Box<dyn Error>> {
+    let range = parse_range(ranges, mode, spelling)?;
+    let mut out = io::stdout().lock();
+    let color = out.is_terminal();
+    write!(out, "{}", grid::render_weighted_range(&range, color))?;
+    Ok(())
+}
+
+fn run_count(ranges: &str, mode: Mode, spelling: Spelling) -> Result<(), Box<dyn Error>> {
+    print_count(
+        &mut io::stdout().lock(),
+        &parse_range(ranges, mode, spelling)?,
+    )
+}
+
+fn print_count(out: &mut impl Write, range: &WeightedRange) -> Result<(), Box<dyn Error>> {
+    writeln!(
+        out,
+        "{:.0} combos ({:.1}%)",
+        range.combo_count(),
+        range.percentage()
+    )?;
+    Ok(())
+}
+
+fn run_convert(
+    ranges: &str,
+    from: Format,
+    to: Format,
+    mode: Mode,
+    spelling: Spelling,
+) -> Result<(), Box<dyn Error>> {
+    let range = from.read(ranges, mode, spelling)?;
+    let output = match to {
+        Format::Notation => formats::write_notation(&range),
+        Format::Solver => formats::write_solver_range(&range),
+        Format::Pokerstove => formats::export_weighted_range(&range, Dialect::PokerStove)?,
+        Format::Flopzilla => formats::export_weighted_range(&range, Dialect::Flopzilla)?,
+    };
+    writeln!(io::stdout().lock(), "{}", output)?;
+    Ok(())
+}
+
+/// Prints how often each range wins all-in, given the `(board, dead)` cards.
+fn run_equity(
+    ranges: &[String],
+    (board, dead_cards): (Option<&str>, Option<&str>),
+    method: Method,
+    mode: Mode,
+    spelling: Spelling,
+) -> Result<(), Box<dyn Error>> {
+    let combo_ranges = ranges
+        .iter()
+        .map(|range| mode.parse_combo_range(range, spelling))
+        .collect::<ParseResult<Vec<_>>>()?;
+    let parse_cards = |cards: Option<&str>| match cards {
+        Some(cards) => Parser::new(cards).with_spelling(spelling).parse_cards(),
+        None => Ok(Vec::new()),
+    };
+    let equities = EquityCalculator::new(combo_ranges)
+        .with_board(&parse_cards(board)?)
+        .with_dead_cards(&parse_cards(dead_cards)?)
+        .with_method(method)
+        .calculate()?;
+    let width = ranges.iter().map(|range| range.chars().count()).max();
+    let mut out = io::stdout().lock();
+    for (range, equity) in ranges.iter().zip(equities) {
+        writeln!(
+            out,
+            "{:width$}  {:6.2}% equity ({:.2}% win, {:.2}% tie)",
+            range,
+            equity.equity * 100.0,
+            equity.win * 100.0,
+            equity.tie * 100.0,
+            width = width.unwrap_or(0)
+        )?;
+    }
+    Ok(())
+}
+
+/// Deals random hands from random positions of a range file and asks what to do with them.
+/// Beginner mode asks raise or fold; advanced mode asks how often to raise.
+fn run_train(file: &str, mode: Mode) -> Result<(), Box<dyn Error>> {
+    let position_inputs = load_range_file(file)?;
+    let charts = position_inputs
+        .iter()
+        .map(Chart::from_position_input)
+        .collect::<Result<Vec<_>, _>>()?;
+    if charts.is_empty() {
+        return Err(format!("{} has no positions", file).into());
+    }
+
+    let mut out = io::stdout().lock();
+    writeln!(out, "Press Ctrl+d to finish\n")?;
+    let mut rng = Rng::from_time();
+    let (mut correct, mut total) = (0, 0);
+    loop {
+        let position_index = rng.below(charts.len());
+        let hand = &VALID_HANDS[rng.below(VALID_HANDS.len())];
+        let [(_, raise_frequency), ..] = charts[position_index].frequencies(hand);
+        match mode {
+            Mode::Beginner => write!(
+                out,
+                "{}: {} - (r)aise or (f)old? ",
+                position_inputs[position_index].position, hand
+            )?,
+            Mode::Advanced => write!(
+                out,
+                "{}: {} - how often do you raise (0-100)? ",
+                position_inputs[position_index].position, hand
+            )?,
+        }
+        out.flush()?;
+
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer)? == 0 {
+            break;
+        }
+        let answer = answer.trim().to_lowercase();
+        let is_correct = match mode {
+            Mode::Beginner => match answer.as_str() {
+                "r" | "raise" => raise_frequency > 0.0,
+                "f" | "fold" => raise_frequency < 1.0,
+                _ => {
+                    writeln!(out, "Answer r or f")?;
+                    continue;
                 }
-                Err(e) => {
-                    panic!("{}", e);
+            },
+            Mode::Advanced => match answer.trim_end_matches('%').parse::<f64>() {
+                Ok(percentage) => (percentage / 100.0 - raise_frequency).abs() <= 0.1,
+                Err(_) => {
+                    writeln!(out, "Answer a number from 0 to 100")?;
+                    continue;
                 }
-            };
+            },
+        };
+        total += 1;
+        if is_correct {
+            correct += 1;
+            writeln!(out, "Correct!")?;
+        } else {
+            writeln!(
+                out,
+                "Wrong: {} raises {:.0}%",
+                hand,
+                raise_frequency * 100.0
+            )?;
         }
-        println!("{:?}", results.into_iter().flatten().collect::<Vec<_>>());
     }
+    writeln!(out, "\n{}/{} correct", correct, total)?;
+    Ok(())
 }
@@ -0,0 +1,714 @@
+use crate::cards::{self, Combo};
+use crate::parser::{
+    CardType, Hand, ParseResult, Parser, SuitedType, WeightedRange, CARD_TYPES, VALID_HANDS,
+};
+use std::fmt::{self, Display};
+
+/// The number of starting hand classes: 13 pairs, 78 suited and 78 offsuit hands.
+pub const TOTAL_HANDS: usize = 169;
+
+/// A fixed-size set of indices backed by `WORDS` 64-bit words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Bits<const WORDS: usize>([u64; WORDS]);
+
+impl<const WORDS: usize> Bits<WORDS> {
+    const EMPTY: Self = Bits([0; WORDS]);
+
+    /// Returns a set with the first `len` indices set.
+    fn filled(len: usize) -> Self {
+        let mut bits = Self::EMPTY;
+        for (word_index, word) in bits.0.iter_mut().enumerate() {
+            let start = word_index * 64;
+            if len >= start + 64 {
+                *word = u64::MAX;
+            } else if len > start {
+                *word = (1 << (len - start)) - 1;
+            }
+        }
+        bits
+    }
+
+    fn get(&self, index: usize) -> bool {
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Sets `index`, returning whether it wasn't already set.
+    fn set(&mut self, index: usize) -> bool {
+        let was_set = self.get(index);
+        self.0[index / 64] |= 1 << (index % 64);
+        !was_set
+    }
+
+    /// Clears `index`, returning whether it was set.
+    fn clear(&mut self, index: usize) -> bool {
+        let was_set = self.get(index);
+        self.0[index / 64] &= !(1 << (index % 64));
+        was_set
+    }
+
+    fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn combine(&self, other: &Self, operation: impl Fn(u64, u64) -> u64) -> Self {
+        let mut result = Self::EMPTY;
+        for (word_index, word) in result.0.iter_mut().enumerate() {
+            *word = operation(self.0[word_index], other.0[word_index]);
+        }
+        result
+    }
+
+    /// Iterates over the set indices in ascending order.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(word_index, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(word_index * 64 + bit)
+            })
+        })
+    }
+}
+
+/// A set of hand classes, stored as one bit per hand in `VALID_HANDS` order so that
+/// membership and set operations take constant time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range {
+    bits: Bits<3>,
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Range { bits: Bits::EMPTY }
+    }
+}
+
+impl Range {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a range containing every starting hand.
+    pub fn full() -> Self {
+        Range {
+            bits: Bits::filled(TOTAL_HANDS),
+        }
+    }
+
+    /// Parses a range string such as `"22+, A2s+, KTo+"` into a `Range`.
+    pub fn parse(range: &str) -> ParseResult<Self> {
+        Parser::new(range).parse_range_set()
+    }
+
+    /// Adds `hand` to the range, returning whether it wasn't already there. Hands that aren't
+    /// one of the 169 classes in `VALID_HANDS` are never added.
+    pub fn insert(&mut self, hand: &Hand) -> bool {
+        match hand.index() {
+            Some(index) => self.bits.set(index),
+            None => false,
+        }
+    }
+
+    /// Removes `hand` from the range, returning whether it was there.
+    pub fn remove(&mut self, hand: &Hand) -> bool {
+        match hand.index() {
+            Some(index) => self.bits.clear(index),
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, hand: &Hand) -> bool {
+        hand.index().is_some_and(|index| self.bits.get(index))
+    }
+
+    /// Returns the hands in either range.
+    pub fn union(&self, other: &Range) -> Range {
+        Range {
+            bits: self.bits.combine(&other.bits, |a, b| a | b),
+        }
+    }
+
+    /// Returns the hands in both ranges.
+    pub fn intersection(&self, other: &Range) -> Range {
+        Range {
+            bits: self.bits.combine(&other.bits, |a, b| a & b),
+        }
+    }
+
+    /// Returns the hands in this range that aren't in `other`, e.g. what CO opens that HJ
+    /// folds is `co.difference(&hj)`.
+    pub fn difference(&self, other: &Range) -> Range {
+        Range {
+            bits: self.bits.combine(&other.bits, |a, b| a & !b),
+        }
+    }
+
+    /// Returns every hand that isn't in this range.
+    pub fn complement(&self) -> Range {
+        Range::full().difference(self)
+    }
+
+    /// Iterates over the hands in the range, strongest first in `VALID_HANDS` order.
+    pub fn iter(&self) -> impl Iterator<Item = &'static Hand> + '_ {
+        self.bits.iter().map(|index| &VALID_HANDS[index])
+    }
+
+    pub fn hands(&self) -> Vec<Hand> {
+        self.iter().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == Bits::EMPTY
+    }
+
+    pub fn combo_count(&self) -> usize {
+        self.iter().map(Hand::combo_count).sum()
+    }
+
+    /// Returns the share of all starting hands in the range, from 0.0 to 100.0.
+    pub fn percentage(&self) -> f64 {
+        cards::combo_percentage(self.combo_count())
+    }
+
+    /// Expands every hand class in the range into its combos.
+    pub fn combos(&self) -> ComboRange {
+        let mut combos = ComboRange::new();
+        for hand in self.iter() {
+            for combo in hand.combos() {
+                combos.insert(&combo);
+            }
+        }
+        combos
+    }
+}
+
+impl<'a> FromIterator<&'a Hand> for Range {
+    fn from_iter<T: IntoIterator<Item = &'a Hand>>(iter: T) -> Self {
+        let mut range = Range::new();
+        for hand in iter {
+            range.insert(hand);
+        }
+        range
+    }
+}
+
+impl FromIterator<Hand> for Range {
+    fn from_iter<T: IntoIterator<Item = Hand>>(iter: T) -> Self {
+        let mut range = Range::new();
+        for hand in iter {
+            range.insert(&hand);
+        }
+        range
+    }
+}
+
+impl Range {
+    /// Writes the range in its shortest standard notation, e.g. `22+, A2s+, K9s+, ATo+`.
+    /// Pairs come first, then hands whose suited and offsuit kickers match, then suited and
+    /// offsuit hands, each from the highest first card down.
+    pub fn to_notation(&self) -> String {
+        let mut pairs: Vec<String> = Vec::new();
+        let mut both: Vec<String> = Vec::new();
+        let mut suited: Vec<String> = Vec::new();
+        let mut offsuit: Vec<String> = Vec::new();
+
+        let pair_runs = runs(0, CARD_TYPES.len() - 1, |rank| {
+            self.contains(&Hand::new(
+                CARD_TYPES[rank],
+                CARD_TYPES[rank],
+                SuitedType::None,
+            ))
+        });
+        for (high, low) in pair_runs {
+            pairs.push(run_notation(None, high, low, 0, &SuitedType::None));
+        }
+
+        for (first, first_card) in CARD_TYPES.iter().enumerate().take(CARD_TYPES.len() - 1) {
+            let kicker_runs = |suited_type: SuitedType| {
+                runs(first + 1, CARD_TYPES.len() - 1, |kicker| {
+                    self.contains(&Hand::new(
+                        *first_card,
+                        CARD_TYPES[kicker],
+                        suited_type.clone(),
+                    ))
+                })
+            };
+            let mut suited_runs = kicker_runs(SuitedType::Suited);
+            let mut offsuit_runs = kicker_runs(SuitedType::Offsuit);
+            // A run shared by suited and offsuit hands drops its suffix.
+            for run in suited_runs.clone() {
+                let (high, low) = run;
+                if offsuit_runs.contains(&run) {
+                    suited_runs.retain(|other| *other != run);
+                    offsuit_runs.retain(|other| *other != run);
+                    both.push(run_notation(
+                        Some(first),
+                        high,
+                        low,
+                        first + 1,
+                        &SuitedType::None,
+                    ));
+                }
+            }
+            for (high, low) in suited_runs {
+                suited.push(run_notation(
+                    Some(first),
+                    high,
+                    low,
+                    first + 1,
+                    &SuitedType::Suited,
+                ));
+            }
+            for (high, low) in offsuit_runs {
+                offsuit.push(run_notation(
+                    Some(first),
+                    high,
+                    low,
+                    first + 1,
+                    &SuitedType::Offsuit,
+                ));
+            }
+        }
+
+        [pairs, both, suited, offsuit].concat().join(", ")
+    }
+}
+
+/// Returns the runs of consecutive rank indices from `start` to `end` for which `included` is
+/// true, as `(highest, lowest)` card indices.
+fn runs(start: usize, end: usize, included: impl Fn(usize) -> bool) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut run_start: Option<usize> = None;
+    for index in start..=end {
+        match (included(index), run_start) {
+            (true, None) => run_start = Some(index),
+            (false, Some(high)) => {
+                runs.push((high, index - 1));
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(high) = run_start {
+        runs.push((high, end));
+    }
+    runs
+}
+
+/// Writes a single run of pairs (`first` is `None`) or of kickers below `first`. Runs that
+/// reach `top` use `+`, and other runs of more than one hand are written from the highest
+/// hand down.
+fn run_notation(
+    first: Option<usize>,
+    high: usize,
+    low: usize,
+    top: usize,
+    suited_type: &SuitedType,
+) -> String {
+    let hand = |kicker: usize| match first {
+        Some(first) => format!("{}{}{}", CARD_TYPES[first], CARD_TYPES[kicker], suited_type),
+        None => format!("{}{}", CARD_TYPES[kicker], CARD_TYPES[kicker]),
+    };
+    if high == low {
+        hand(high)
+    } else if high == top {
+        format!("{}+", hand(low))
+    } else {
+        format!("{}-{}", hand(high), hand(low))
+    }
+}
+
+/// Compresses a list of hands into the shortest standard range notation.
+pub fn compress(hands: &[Hand]) -> String {
+    hands.iter().collect::<Range>().to_notation()
+}
+
+/// Rewrites a range string in its canonical shortest form, so that equivalent ranges written
+/// differently produce the same string.
+pub fn normalize(range: &str) -> ParseResult<String> {
+    Ok(Range::parse(range)?.to_notation())
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_notation())
+    }
+}
+
+/// A set of concrete combos, stored as one bit for each of the 1326 starting hands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ComboRange {
+    bits: Bits<21>,
+}
+
+impl Default for ComboRange {
+    fn default() -> Self {
+        ComboRange { bits: Bits::EMPTY }
+    }
+}
+
+impl ComboRange {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a range containing every combo.
+    pub fn full() -> Self {
+        ComboRange {
+            bits: Bits::filled(cards::TOTAL_COMBOS),
+        }
+    }
+
+    /// Parses a range string of hand classes and explicit combos into a `ComboRange`.
+    pub fn parse(range: &str) -> ParseResult<Self> {
+        Parser::new(range).parse_combo_set()
+    }
+
+    /// Adds `combo` to the range, returning whether it wasn't already there.
+    pub fn insert(&mut self, combo: &Combo) -> bool {
+        self.bits.set(combo.index())
+    }
+
+    /// Removes `combo` from the range, returning whether it was there.
+    pub fn remove(&mut self, combo: &Combo) -> bool {
+        self.bits.clear(combo.index())
+    }
+
+    pub fn contains(&self, combo: &Combo) -> bool {
+        self.bits.get(combo.index())
+    }
+
+    pub fn union(&self, other: &ComboRange) -> ComboRange {
+        ComboRange {
+            bits: self.bits.combine(&other.bits, |a, b| a | b),
+        }
+    }
+
+    pub fn intersection(&self, other: &ComboRange) -> ComboRange {
+        ComboRange {
+            bits: self.bits.combine(&other.bits, |a, b| a & b),
+        }
+    }
+
+    pub fn difference(&self, other: &ComboRange) -> ComboRange {
+        ComboRange {
+            bits: self.bits.combine(&other.bits, |a, b| a & !b),
+        }
+    }
+
+    pub fn complement(&self) -> ComboRange {
+        ComboRange::full().difference(self)
+    }
+
+    /// Iterates over the combos in the range in `Combo::index` order.
+    pub fn iter(&self) -> impl Iterator<Item = Combo> + '_ {
+        self.bits.iter().map(Combo::from_index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == Bits::EMPTY
+    }
+
+    /// Returns the share of all starting hands in the range, from 0.0 to 100.0.
+    pub fn percentage(&self) -> f64 {
+        cards::combo_percentage(self.len())
+    }
+}
+
+impl<'a> FromIterator<&'a Combo> for ComboRange {
+    fn from_iter<T: IntoIterator<Item = &'a Combo>>(iter: T) -> Self {
+        let mut range = ComboRange::new();
+        for combo in iter {
+            range.insert(combo);
+        }
+        range
+    }
+}
+
+impl FromIterator<Combo> for ComboRange {
+    fn from_iter<T: IntoIterator<Item = Combo>>(iter: T) -> Self {
+        let mut range = ComboRange::new();
+        for combo in iter {
+            range.insert(&combo);
+        }
+        range
+    }
+}
+
+impl From<&Range> for ComboRange {
+    fn from(range: &Range) -> Self {
+        range.combos()
+    }
+}
+
+/// A frequency between 0.0 and 1.0 for each of the 1326 combos, so that hands of the same
+/// class can be played at different frequencies, as solvers do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeightedComboRange {
+    frequencies: Vec<f64>,
+}
+
+impl Default for WeightedComboRange {
+    fn default() -> Self {
+        WeightedComboRange {
+            frequencies: vec![0.0; cards::TOTAL_COMBOS],
+        }
+    }
+}
+
+impl WeightedComboRange {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, combo: &Combo, frequency: f64) {
+        self.frequencies[combo.index()] = frequency;
+    }
+
+    /// Sets the frequency of every combo of `hand`.
+    pub fn set_hand(&mut self, hand: &Hand, frequency: f64) {
+        for combo in hand.combos() {
+            self.set(&combo, frequency);
+        }
+    }
+
+    pub fn frequency(&self, combo: &Combo) -> f64 {
+        self.frequencies[combo.index()]
+    }
+
+    /// Returns the frequency shared by every combo of `hand`, or `None` if its combos are
+    /// played at different frequencies.
+    pub fn hand_frequency(&self, hand: &Hand) -> Option<f64> {
+        let mut frequencies = hand
+            .combos()
+            .into_iter()
+            .map(|combo| self.frequency(&combo));
+        let first = frequencies.next()?;
+        frequencies
+            .all(|frequency| frequency == first)
+            .then_some(first)
+    }
+
+    /// Returns the combos played at a non-zero frequency.
+    pub fn combos(&self) -> ComboRange {
+        (0..cards::TOTAL_COMBOS)
+            .filter(|index| self.frequencies[*index] > 0.0)
+            .map(Combo::from_index)
+            .collect()
+    }
+
+    /// Returns the number of combos in the range, each counted at its frequency.
+    pub fn combo_count(&self) -> f64 {
+        self.frequencies.iter().sum()
+    }
+
+    /// Collapses the range to one frequency per hand class, averaging over the combos of any
+    /// class whose combos are played at different frequencies.
+    pub fn to_weighted_range(&self) -> WeightedRange {
+        let mut range = WeightedRange::new();
+        for hand in VALID_HANDS.iter() {
+            let combos = hand.combos();
+            let total: f64 = combos.iter().map(|combo| self.frequency(combo)).sum();
+            if total > 0.0 {
+                range.insert(hand.clone(), total / combos.len() as f64);
+            }
+        }
+        range
+    }
+}
+
+impl From<&WeightedRange> for WeightedComboRange {
+    fn from(range: &WeightedRange) -> Self {
+        let mut combo_range = WeightedComboRange::new();
+        for (hand, frequency) in range.iter() {
+            combo_range.set_hand(hand, frequency);
+        }
+        combo_range
+    }
+}
+
+impl From<&ComboRange> for WeightedComboRange {
+    fn from(range: &ComboRange) -> Self {
+        let mut combo_range = WeightedComboRange::new();
+        for combo in range.iter() {
+            combo_range.set(&combo, 1.0);
+        }
+        combo_range
+    }
+}
+
+/// Builds a family of hands whose two ranks move together, like suited connectors or
+/// one-gappers, e.g. `Connectors::new(0).with_suited_type(SuitedType::Suited)
+/// .with_first_cards(CardType::Five, CardType::Ace)` for `54s, 65s, ..., AKs`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connectors {
+    gap: usize,
+    suited_type: SuitedType,
+    lowest: CardType,
+    highest: CardType,
+}
+
+impl Connectors {
+    /// Starts a family with `gap` ranks missing between the two cards (0 for connectors, 1
+    /// for one-gappers), suited and offsuit, at every rank.
+    pub fn new(gap: usize) -> Self {
+        Connectors {
+            gap,
+            suited_type: SuitedType::None,
+            lowest: CardType::Two,
+            highest: CardType::Ace,
+        }
+    }
+
+    /// Limits the family to suited or offsuit hands. `SuitedType::None` takes both.
+    pub fn with_suited_type(mut self, suited_type: SuitedType) -> Self {
+        self.suited_type = suited_type;
+        self
+    }
+
+    /// Limits the family to hands whose first card is between `low` and `high`, in either
+    /// order.
+    pub fn with_first_cards(mut self, low: CardType, high: CardType) -> Self {
+        self.lowest = low.min(high);
+        self.highest = low.max(high);
+        self
+    }
+
+    pub fn hands(&self) -> Vec<Hand> {
+        self.range().hands()
+    }
+
+    /// Returns the family as a `Range`, built straight from the ranks of each hand.
+    pub fn range(&self) -> Range {
+        let suited_types: &[SuitedType] = match self.suited_type {
+            SuitedType::None => &[SuitedType::Suited, SuitedType::Offsuit],
+            SuitedType::Suited => &[SuitedType::Suited],
+            SuitedType::Offsuit => &[SuitedType::Offsuit],
+        };
+        let mut range = Range::new();
+        for first_card in CARD_TYPES
+            .iter()
+            .filter(|card| (self.lowest..=self.highest).contains(card))
+        {
+            let Some(second_card) = CARD_TYPES.get(first_card.index() + self.gap + 1) else {
+                continue;
+            };
+            for suited_type in suited_types {
+                range.insert(&Hand::new(*first_card, *second_card, suited_type.clone()));
+            }
+        }
+        range
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combines_ranges() {
+        let cutoff = Range::parse("22+, A2s+, KTs+, ATo+").unwrap();
+        let hijack = Range::parse("55+, A9s+, KJs+, AJo+").unwrap();
+        assert_eq!(
+            cutoff.difference(&hijack),
+            Range::parse("22-44, A2s-A8s, KTs, ATo").unwrap()
+        );
+        assert_eq!(cutoff.intersection(&hijack), hijack);
+        assert_eq!(cutoff.union(&hijack), cutoff);
+        assert_eq!(cutoff.complement().len(), 169 - cutoff.len());
+        assert!(cutoff.complement().intersection(&cutoff).is_empty());
+    }
+
+    #[test]
+    fn tracks_membership() {
+        let mut range = Range::parse("AA, AKs").unwrap();
+        let ace_king_suited = Parser::new("AKs").parse_range().unwrap().remove(0);
+        let ace_king_offsuit = Parser::new("AKo").parse_range().unwrap().remove(0);
+        assert!(range.contains(&ace_king_suited));
+        assert!(!range.contains(&ace_king_offsuit));
+        assert!(!range.insert(&ace_king_suited));
+        assert!(range.insert(&ace_king_offsuit));
+        assert!(range.remove(&ace_king_suited));
+        assert_eq!(range.to_string(), "AA, AKo");
+        assert_eq!(range.combo_count(), 18);
+    }
+
+    #[test]
+    fn compresses_ranges() {
+        let range = Range::parse("22+, A2s+, K9s+, ATo+").unwrap();
+        assert_eq!(range.to_notation(), "22+, A2s+, K9s+, ATo+");
+        assert_eq!(
+            normalize("AKs, AKo, QQ, JJ, TT, 55, AQs, AJs, A5s, A4s, K9o, KTo, KJo, KQo").unwrap(),
+            "QQ-TT, 55, AJs+, A5s-A4s, AKo, K9o+"
+        );
+        assert_eq!(
+            normalize("A2s, A3s, A2o, A3o, KQs, KJs, KQo").unwrap(),
+            "A3-A2, KJs+, KQo"
+        );
+        assert_eq!(normalize("K9-K7, 44-22").unwrap(), "44-22, K9-K7");
+        assert_eq!(
+            normalize("xx").unwrap(),
+            "22+, A2+, K2+, Q2+, J2+, T2+, 92+, 82+, 72+, 62+, 52+, 42+, 32"
+        );
+        assert_eq!(Range::new().to_notation(), "");
+    }
+
+    #[test]
+    fn round_trips_compressed_ranges() {
+        let charts = [
+            "AQo+, A5s, A9s+, KTs+, QTs+, JTs+, 77+",
+            "A9o+, KTo+, QJo, A2s+, K8s+, Q9s+, J9s+, T8s+, 97s+, 86s+, 75s+, 65s+, 54s, 44+",
+            "A2o-A5o, K7o, Q8o, J8o, T8o, 97o, 87o, 76o, Q2s-Q4s, J2s-J5s, T2s-T5s, 92s-95s",
+            "22-44, 88, JJ-QQ, AKs, A2o, K3s-K5s, 32s",
+        ];
+        for chart in charts {
+            let range = Range::parse(chart).unwrap();
+            assert_eq!(Range::parse(&range.to_notation()).unwrap(), range);
+        }
+    }
+
+    #[test]
+    fn fills_whole_ranges() {
+        assert_eq!(Range::full().len(), TOTAL_HANDS);
+        assert_eq!(Range::full().combo_count(), cards::TOTAL_COMBOS);
+        assert_eq!(ComboRange::full().len(), cards::TOTAL_COMBOS);
+        assert_eq!(Range::full().combos(), ComboRange::full());
+    }
+
+    #[test]
+    fn combines_combo_ranges() {
+        let range = ComboRange::parse("AA, AKs").unwrap();
+        let blockers = ComboRange::parse("AhKh, AsAh, AhAd, AhAc").unwrap();
+        assert_eq!(range.len(), 10);
+        assert_eq!(range.difference(&blockers).len(), 6);
+        assert_eq!(range.intersection(&blockers).len(), 4);
+        assert_eq!(range.complement().len(), cards::TOTAL_COMBOS - 10);
+        assert!(range.iter().all(|combo| range.contains(&combo)));
+    }
+
+    #[test]
+    fn builds_connectors() {
+        let suited_connectors = Connectors::new(0)
+            .with_suited_type(SuitedType::Suited)
+            .with_first_cards(CardType::Five, CardType::Ace);
+        assert_eq!(
+            Range::from_iter(suited_connectors.hands()),
+            Range::parse("54s, 65s, 76s, 87s, 98s, T9s, JTs, QJs, KQs, AKs").unwrap()
+        );
+        let one_gappers = Connectors::new(1).with_first_cards(CardType::Ten, CardType::Five);
+        assert_eq!(one_gappers.hands().len(), 12);
+        assert_eq!(Connectors::new(11).hands().len(), 2);
+    }
+}
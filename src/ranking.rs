@@ -0,0 +1,140 @@
+use crate::cards;
+use crate::parser::{Hand, ParseResult, Parser, VALID_HANDS};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// An order of the starting hands from strongest to weakest, used to expand percentile ranges
+/// such as `top 15%`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Ranking {
+    /// By all-in equity against one random hand.
+    #[default]
+    Equity,
+    /// By Sklansky-Chubukov number, which favours hands that do well all-in against the hands
+    /// that call them, like suited aces, over hands that play well against anything.
+    SklanskyChubukov,
+    /// A user-supplied order, strongest first. Hands left out of it are never included.
+    Custom(Vec<Hand>),
+}
+
+impl Ranking {
+    /// Reads a custom ranking from a range string such as `"AA, KK, AKs, QQ"`, keeping the
+    /// hands in the order they're listed.
+    pub fn parse(order: &str) -> ParseResult<Self> {
+        Ok(Ranking::Custom(Parser::new(order).parse_range_list()?))
+    }
+
+    /// Returns the ranked hands, strongest first.
+    pub fn hands(&self) -> Vec<Hand> {
+        self.order().to_vec()
+    }
+
+    /// The ranked hands, with the built-in orders looked up from their tables only once.
+    fn order(&self) -> &[Hand] {
+        static EQUITY: OnceLock<Vec<Hand>> = OnceLock::new();
+        static SKLANSKY_CHUBUKOV: OnceLock<Vec<Hand>> = OnceLock::new();
+        match self {
+            Ranking::Equity => EQUITY.get_or_init(|| hands_named(&EQUITY_ORDER)),
+            Ranking::SklanskyChubukov => {
+                SKLANSKY_CHUBUKOV.get_or_init(|| hands_named(&SKLANSKY_CHUBUKOV_ORDER))
+            }
+            Ranking::Custom(hands) => hands,
+        }
+    }
+
+    /// Returns the strongest hands that together make up `percentage` percent of all combos.
+    /// A hand that straddles the cut-off is included if that lands closer to `percentage`.
+    pub fn top(&self, percentage: f64) -> Vec<Hand> {
+        let target = percentage / 100.0 * cards::TOTAL_COMBOS as f64;
+        let mut combo_count = 0;
+        self.order()
+            .iter()
+            .take_while(|hand| {
+                let below = target - combo_count as f64;
+                combo_count += hand.combo_count();
+                combo_count as f64 - target <= below
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+/// Turns a table of hand names into the hands they name.
+fn hands_named(names: &[&str]) -> Vec<Hand> {
+    let hands: HashMap<String, &Hand> = VALID_HANDS
+        .iter()
+        .map(|hand| (hand.to_string(), hand))
+        .collect();
+    names
+        .iter()
+        .filter_map(|name| hands.get(*name).copied().cloned())
+        .collect()
+}
+
+/// The starting hands ordered by all-in equity against one random hand.
+const EQUITY_ORDER: [&str; 169] = [
+    "AA", "KK", "QQ", "JJ", "TT", "99", "88", "AKs", "77", "AQs", "AJs", "AKo", "ATs", "AQo",
+    "AJo", "KQs", "66", "A9s", "ATo", "KJs", "A8s", "KTs", "KQo", "A7s", "A9o", "KJo", "55", "QJs",
+    "K9s", "A6s", "A5s", "A8o", "KTo", "QTs", "A4s", "A7o", "K8s", "A3s", "QJo", "K9o", "A6o",
+    "Q9s", "JTs", "K7s", "A2s", "A5o", "QTo", "44", "A4o", "K6s", "A3o", "K8o", "Q8s", "K5s",
+    "J9s", "Q9o", "JTo", "K7o", "K4s", "A2o", "Q7s", "K6o", "T9s", "K3s", "J8s", "33", "Q6s",
+    "Q8o", "J9o", "K5o", "K2s", "Q5s", "T8s", "J7s", "K4o", "Q4s", "Q7o", "T9o", "K3o", "J8o",
+    "Q3s", "Q6o", "98s", "J6s", "T7s", "K2o", "22", "Q2s", "J5s", "Q5o", "T8o", "J7o", "J4s",
+    "Q4o", "97s", "T6s", "Q3o", "J3s", "87s", "98o", "T7o", "J6o", "96s", "Q2o", "J2s", "J5o",
+    "T5s", "T4s", "97o", "86s", "T6o", "J4o", "95s", "T3s", "76s", "J3o", "87o", "T2s", "85s",
+    "96o", "J2o", "T5o", "75s", "94s", "T4o", "93s", "86o", "65s", "84s", "T3o", "95o", "92s",
+    "76o", "74s", "T2o", "54s", "85o", "64s", "83s", "94o", "75o", "82s", "65o", "93o", "73s",
+    "53s", "63s", "84o", "92o", "74o", "43s", "72s", "64o", "54o", "52s", "62s", "83o", "82o",
+    "42s", "73o", "53o", "63o", "32s", "43o", "72o", "52o", "62o", "42o", "32o",
+];
+
+/// The starting hands ordered by Sklansky-Chubukov number: how deep you could move all-in
+/// from the small blind and still profit against an opponent who sees your cards.
+const SKLANSKY_CHUBUKOV_ORDER: [&str; 169] = [
+    "AA", "KK", "AKs", "QQ", "JJ", "AKo", "AQs", "TT", "99", "AJs", "AQo", "88", "ATs", "77",
+    "AJo", "66", "A9s", "ATo", "55", "A8s", "44", "A7s", "A9o", "KQs", "A5s", "A6s", "A8o", "KJs",
+    "A4s", "33", "A3s", "A7o", "KTs", "A2s", "A5o", "A6o", "KQo", "A4o", "22", "KJo", "A3o", "K9s",
+    "QJs", "A2o", "KTo", "QTs", "K8s", "K7s", "K9o", "JTs", "K6s", "K5s", "Q9s", "QJo", "K8o",
+    "K4s", "K3s", "K7o", "QTo", "K6o", "K2s", "Q8s", "K5o", "J9s", "K4o", "Q9o", "K3o", "Q7s",
+    "T9s", "JTo", "Q6s", "K2o", "J8s", "Q5s", "Q8o", "Q4s", "Q3s", "Q7o", "J7s", "J9o", "Q2s",
+    "T8s", "Q6o", "Q5o", "98s", "Q4o", "J8o", "J6s", "T9o", "Q3o", "J5s", "T7s", "Q2o", "J4s",
+    "J7o", "J3s", "97s", "T8o", "T6s", "J2s", "J6o", "87s", "J5o", "T5s", "J4o", "T7o", "96s",
+    "98o", "T4s", "J3o", "T3s", "J2o", "86s", "T6o", "T2s", "76s", "95s", "97o", "T5o", "T4o",
+    "87o", "85s", "94s", "96o", "93s", "T3o", "75s", "65s", "T2o", "92s", "95o", "86o", "84s",
+    "76o", "74s", "54s", "94o", "85o", "93o", "83s", "64s", "82s", "92o", "75o", "73s", "84o",
+    "65o", "53s", "63s", "74o", "83o", "72s", "43s", "82o", "64o", "54o", "62s", "73o", "52s",
+    "63o", "42s", "53o", "72o", "32s", "43o", "62o", "52o", "42o", "32o",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range::Range;
+
+    #[test]
+    fn ranks_every_hand() {
+        for ranking in [Ranking::Equity, Ranking::SklanskyChubukov] {
+            let hands = ranking.hands();
+            assert_eq!(hands.len(), VALID_HANDS.len());
+            assert_eq!(hands.iter().collect::<Range>(), Range::full());
+        }
+    }
+
+    #[test]
+    fn takes_the_top_hands() {
+        let parse = |range: &str| Range::parse(range).unwrap();
+        assert!(Ranking::Equity.top(0.0).is_empty());
+        assert_eq!(Ranking::Equity.top(100.0).len(), VALID_HANDS.len());
+        assert_eq!(
+            Ranking::Equity.top(2.0).iter().collect::<Range>(),
+            parse("JJ+")
+        );
+        assert_eq!(
+            Ranking::SklanskyChubukov.top(2.0).iter().collect::<Range>(),
+            parse("JJ+, AKs")
+        );
+
+        let ranking = Ranking::parse("AKs, 22, AA").unwrap();
+        assert_eq!(ranking.top(0.75), Range::parse("AKs, 22").unwrap().hands());
+    }
+}
@@ -8,19 +8,18 @@ const CARDS: [&str; 13] = [
 
 pub fn generate_hands_list() {
     let mut file = OpenOptions::new()
-        .write(true)
         .append(true)
         .create(true)
         .open("basic.range")
         .unwrap();
 
-    for i in 0..CARDS.len() {
-        for j in 0..CARDS.len() {
+    for (i, first_card) in CARDS.iter().enumerate() {
+        for (j, second_card) in CARDS.iter().enumerate() {
             if i == j {
                 if let Err(e) = writeln!(
                     file,
                     "Hand(CardType::{}, CardType::{}, SuitedType::None),",
-                    CARDS[i], CARDS[j]
+                    first_card, second_card
                 ) {
                     eprintln!("Couldn't write to file: {}", e);
                 }
@@ -37,7 +36,7 @@ pub fn generate_hands_list() {
                         if let Err(e) = writeln!(
                             file,
                             "Hand(CardType::{}, CardType::{}, {}),",
-                            CARDS[i], CARDS[j], suited
+                            first_card, second_card, suited
                         ) {
                             eprintln!("Couldn't write to file: {}", e);
                         }
@@ -47,3 +46,49 @@ pub fn generate_hands_list() {
         }
     }
 }
+
+/// A small xorshift random number generator. It's seedable so that sampled results can be
+/// reproduced, but isn't suitable for anything security related.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Mix the seed so that small seeds don't start with mostly zero bits, and never let
+        // the state be zero, which xorshift can't leave.
+        let mut state = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        state ^= state >> 31;
+        Rng {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    /// Seeds a generator from the current time.
+    pub fn from_time() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64);
+        Rng::new(nanos)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Returns a number from 0.0 up to but not including 1.0.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns a number from 0 up to but not including `bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}